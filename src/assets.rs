@@ -0,0 +1,456 @@
+//! Sprite asset files.
+//!
+//! Each species is described by a plain text `.sprite` file with one section per
+//! life stage. A section lists its palette and one or more animation frames drawn
+//! as a grid of characters:
+//!
+//! ```text
+//! # Lines starting with '#' are comments
+//! species blob
+//!
+//! stage child          # egg, baby, child, teen, adult or elder
+//! cell 2               # size of one grid cell in playground units
+//! color Y yellow       # palette entry: character and color name or #rrggbb
+//! face 4 2 2           # optional: column, row and radius (in cells) of the face
+//! frame 900            # a frame shown for 900ms, followed by its rows
+//! ..Y...Y..
+//! .YYYYYYY.
+//! YYYYYYYYY
+//! .YYYYYYY.
+//! ```
+//!
+//! A `#` with spaces on either side starts a comment that runs to the end of the
+//! line, so colors like `#ffcc00` are left alone. `.` marks a transparent cell. Grid rows can't contain spaces, so any line with a
+//! space in it starts a new directive. The eyes and mouth are drawn over the face
+//! area according to the pet's mood.
+//!
+//! Default sprites are built into the binary. A file with the same name in
+//! `$XDG_CONFIG_HOME/tamatui/sprites/` replaces the stages it defines.
+
+use std::{collections::HashMap, fs, path::PathBuf, str::FromStr, time::Duration};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use ratatui::style::Color;
+use tamatui_core::stage::Stage;
+
+pub const DEFAULT_SPECIES: &str = "blob";
+
+/// Sprite files shipped with the game.
+const BUILT_IN: &[(&str, &str)] = &[("blob", include_str!("../assets/sprites/blob.sprite"))];
+
+/// Every stage needs a sprite except [`Stage::Dead`], which gets a memorial instead.
+const DRAWN_STAGES: [Stage; 6] = [
+    Stage::Egg,
+    Stage::Baby,
+    Stage::Child,
+    Stage::Teen,
+    Stage::Adult,
+    Stage::Elder,
+];
+
+/// All the sprites for one species.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSet {
+    pub species: String,
+    stages: HashMap<Stage, StageSprite>,
+}
+
+/// The animation for one life stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageSprite {
+    /// Size of a grid cell in playground units.
+    pub cell: f64,
+    pub face: Option<Face>,
+    pub frames: Vec<Frame>,
+}
+
+/// Where to draw the eyes and mouth, in grid cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub col: f64,
+    pub row: f64,
+    pub radius: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub duration: Duration,
+    pub width: usize,
+    pub height: usize,
+    /// Row-major colors, `None` for transparent cells.
+    pub pixels: Vec<Option<Color>>,
+}
+
+impl SpriteSet {
+    pub fn get(&self, stage: Stage) -> Option<&StageSprite> {
+        self.stages.get(&stage)
+    }
+}
+
+impl StageSprite {
+    /// The frame showing at `time` into the looping animation.
+    pub fn frame_at(&self, time: Duration) -> &Frame {
+        let total: u128 = self.frames.iter().map(|f| f.duration.as_millis()).sum();
+        let mut t = time.as_millis() % total.max(1);
+        for frame in &self.frames {
+            if t < frame.duration.as_millis() {
+                return frame;
+            }
+            t -= frame.duration.as_millis();
+        }
+        &self.frames[0]
+    }
+}
+
+impl Frame {
+    pub fn pixel(&self, col: usize, row: usize) -> Option<Color> {
+        self.pixels[row * self.width + col]
+    }
+}
+
+/// Directory searched for sprite files that replace the built-in ones.
+pub fn override_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("tamatui").join("sprites"))
+}
+
+/// The sprites for `species` that ship with the game, whatever is in
+/// [`override_dir`]. Empty for a species without any.
+pub fn built_in(species: &str) -> Result<SpriteSet> {
+    match BUILT_IN.iter().find(|(name, _)| *name == species) {
+        Some((_, source)) => {
+            parse(source).wrap_err_with(|| format!("built-in sprites for {species}"))
+        }
+        None => Ok(SpriteSet {
+            species: species.to_string(),
+            stages: HashMap::new(),
+        }),
+    }
+}
+
+/// Loads the sprites for `species`: the built-in ones, if any, with stages from a
+/// file in [`override_dir`] taking precedence.
+pub fn load(species: &str) -> Result<SpriteSet> {
+    let mut set = built_in(species)?;
+
+    if let Some(path) = override_dir().map(|dir| dir.join(format!("{species}.sprite"))) {
+        if path.exists() {
+            let source = fs::read_to_string(&path)
+                .wrap_err_with(|| format!("reading {}", path.display()))?;
+            let custom = parse(&source).wrap_err_with(|| format!("in {}", path.display()))?;
+            if custom.species != species {
+                bail!(
+                    "{} describes species {:?} but is named after {species:?}",
+                    path.display(),
+                    custom.species
+                );
+            }
+            set.stages.extend(custom.stages);
+        }
+    }
+
+    for stage in DRAWN_STAGES {
+        if set.get(stage).is_none() {
+            bail!("no {} sprite for species {species:?}", stage.name());
+        }
+    }
+    Ok(set)
+}
+
+/// A stage section while it's being read.
+struct Section {
+    stage: Stage,
+    line: usize,
+    cell: f64,
+    face: Option<Face>,
+    palette: HashMap<char, Color>,
+    /// Frame duration, the line it started on and its rows.
+    frames: Vec<(Duration, usize, Vec<String>)>,
+}
+
+/// Parses a sprite file, checking that every section is complete and consistent.
+pub fn parse(source: &str) -> Result<SpriteSet> {
+    let mut species = None;
+    let mut stages = HashMap::new();
+    let mut section: Option<Section> = None;
+
+    for (index, raw) in source.lines().enumerate() {
+        let number = index + 1;
+        let line = strip_comment(raw).trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at_line = || format!("line {number}");
+
+        // Rows have no spaces in them, anything else is a directive
+        if !line.contains(char::is_whitespace) {
+            let frame = section
+                .as_mut()
+                .and_then(|section| section.frames.last_mut())
+                .ok_or_else(|| eyre!("unexpected {line:?} outside of a frame"))
+                .wrap_err_with(at_line)?;
+            frame.2.push(line.to_string());
+            continue;
+        }
+
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args: Vec<&str> = rest.split_whitespace().collect();
+        match keyword {
+            "species" => {
+                let [name] = args[..] else {
+                    bail!("{}: expected `species <name>`", at_line());
+                };
+                if species.is_some() {
+                    bail!("{}: species is given twice", at_line());
+                }
+                species = Some(name.to_string());
+            }
+            "stage" => {
+                let [name] = args[..] else {
+                    bail!("{}: expected `stage <name>`", at_line());
+                };
+                let stage = DRAWN_STAGES
+                    .into_iter()
+                    .find(|stage| stage.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| eyre!("{}: unknown stage {name:?}", at_line()))?;
+                if let Some(done) = section.take() {
+                    finish(done, &mut stages)?;
+                }
+                if stages.contains_key(&stage) {
+                    bail!("{}: stage {name} is defined twice", at_line());
+                }
+                section = Some(Section {
+                    stage,
+                    line: number,
+                    cell: 1.0,
+                    face: None,
+                    palette: HashMap::new(),
+                    frames: Vec::new(),
+                });
+            }
+            "cell" | "color" | "face" | "frame" => {
+                let section = section
+                    .as_mut()
+                    .ok_or_else(|| eyre!("{}: `{keyword}` before any `stage`", at_line()))?;
+                directive(section, keyword, &args, number).wrap_err_with(at_line)?;
+            }
+            _ => bail!("{}: unknown directive {keyword:?}", at_line()),
+        }
+    }
+    if let Some(done) = section.take() {
+        finish(done, &mut stages)?;
+    }
+
+    let species = species.ok_or_else(|| eyre!("missing `species <name>` line"))?;
+    Ok(SpriteSet { species, stages })
+}
+
+/// Cuts `line` off at a trailing comment: a `#` with spaces on either side.
+fn strip_comment(line: &str) -> &str {
+    line.match_indices('#')
+        .find(|&(at, _)| {
+            line[..at].ends_with(char::is_whitespace)
+                && line[at + 1..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map_or(line, |(at, _)| &line[..at])
+}
+
+fn directive(section: &mut Section, keyword: &str, args: &[&str], line: usize) -> Result<()> {
+    match (keyword, args) {
+        ("cell", [size]) => {
+            section.cell = parse_number(size)?;
+            if section.cell <= 0.0 {
+                bail!("cell size must be positive");
+            }
+        }
+        ("color", [key, color]) => {
+            let mut chars = key.chars();
+            let (Some(key), None) = (chars.next(), chars.next()) else {
+                bail!("palette keys are single characters, got {key:?}");
+            };
+            if key == '.' || key == '#' {
+                bail!("{key:?} can't be used as a palette key");
+            }
+            let color = Color::from_str(color).map_err(|_| eyre!("unknown color {color:?}"))?;
+            if section.palette.insert(key, color).is_some() {
+                bail!("color {key:?} is defined twice");
+            }
+        }
+        ("face", [col, row, radius]) => {
+            section.face = Some(Face {
+                col: parse_number(col)?,
+                row: parse_number(row)?,
+                radius: parse_number(radius)?,
+            });
+        }
+        ("frame", [millis]) => {
+            let millis: u64 = millis
+                .parse()
+                .map_err(|_| eyre!("frame duration must be whole milliseconds, got {millis:?}"))?;
+            if millis == 0 {
+                bail!("frame duration must be more than 0ms");
+            }
+            section
+                .frames
+                .push((Duration::from_millis(millis), line, Vec::new()));
+        }
+        _ => bail!("wrong number of arguments for `{keyword}`"),
+    }
+    Ok(())
+}
+
+fn parse_number(value: &str) -> Result<f64> {
+    value
+        .parse()
+        .map_err(|_| eyre!("expected a number, got {value:?}"))
+}
+
+/// Validates a finished section and turns its rows into frames.
+fn finish(section: Section, stages: &mut HashMap<Stage, StageSprite>) -> Result<()> {
+    let name = section.stage.name().to_lowercase();
+    let context = || format!("stage {name} (line {})", section.line);
+    if section.frames.is_empty() {
+        bail!("{}: has no frames", context());
+    }
+
+    let mut frames = Vec::new();
+    let mut size = None;
+    for (duration, line, rows) in &section.frames {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if height == 0 || width == 0 {
+            bail!("{}: frame on line {line} is empty", context());
+        }
+        if *size.get_or_insert((width, height)) != (width, height) {
+            bail!(
+                "{}: frame on line {line} is {width}x{height} but earlier frames are {}x{}",
+                context(),
+                size.unwrap().0,
+                size.unwrap().1
+            );
+        }
+        let mut pixels = Vec::with_capacity(width * height);
+        for (offset, row) in rows.iter().enumerate() {
+            let row_line = line + 1 + offset;
+            if row.chars().count() != width {
+                bail!(
+                    "{}: row on line {row_line} is {} wide, expected {width}",
+                    context(),
+                    row.chars().count()
+                );
+            }
+            for key in row.chars() {
+                if key == '.' {
+                    pixels.push(None);
+                    continue;
+                }
+                let color = section.palette.get(&key).ok_or_else(|| {
+                    eyre!(
+                        "{}: {key:?} on line {row_line} isn't in the palette",
+                        context()
+                    )
+                })?;
+                pixels.push(Some(*color));
+            }
+        }
+        frames.push(Frame {
+            duration: *duration,
+            width,
+            height,
+            pixels,
+        });
+    }
+
+    if let (Some(face), Some((width, height))) = (section.face, size) {
+        if face.col >= width as f64 || face.row >= height as f64 || face.radius <= 0.0 {
+            bail!("{}: face is outside the {width}x{height} grid", context());
+        }
+    }
+
+    stages.insert(
+        section.stage,
+        StageSprite {
+            cell: section.cell,
+            face: section.face,
+            frames,
+        },
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_sprites_are_valid() {
+        for (species, source) in BUILT_IN {
+            let set = parse(source).unwrap();
+            assert_eq!(set.species, *species);
+            for stage in DRAWN_STAGES {
+                assert!(set.get(stage).is_some(), "{species} has no {stage:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn the_documented_example_parses() {
+        let source = include_str!("assets.rs");
+        let example: String = source
+            .lines()
+            .filter_map(|line| line.strip_prefix("//!"))
+            .skip_while(|line| !line.contains("```text"))
+            .skip(1)
+            .take_while(|line| !line.contains("```"))
+            .map(|line| format!("{}\n", line.trim_start()))
+            .collect();
+        let set = parse(&example).unwrap();
+        assert_eq!(set.species, "blob");
+        let child = set.get(Stage::Child).unwrap();
+        assert_eq!(child.cell, 2.0);
+        assert_eq!((child.frames[0].width, child.frames[0].height), (9, 4));
+        assert_eq!(
+            child.frames[0].pixels[2],
+            Some(Color::Yellow),
+            "the color's trailing comment isn't part of it"
+        );
+    }
+
+    #[test]
+    fn frames_loop() {
+        let set =
+            parse("species test\nstage egg\ncolor W white\nframe 100\nW.\nframe 50\n.W\n").unwrap();
+        let egg = set.get(Stage::Egg).unwrap();
+        assert_eq!(
+            egg.frame_at(Duration::from_millis(120)).pixel(1, 0),
+            Some(Color::White)
+        );
+        assert_eq!(
+            egg.frame_at(Duration::from_millis(160)).pixel(0, 0),
+            Some(Color::White)
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let err = parse("species test\nstage egg\ncolor W white\nframe 100\nWX\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stage egg (line 2): 'X' on line 5 isn't in the palette"
+        );
+
+        let err = parse("species test\nstage egg\nframe 100\n..\n...\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stage egg (line 2): row on line 5 is 3 wide, expected 2"
+        );
+
+        let err = parse("species test\nstage yolk\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: unknown stage \"yolk\"");
+    }
+}
@@ -0,0 +1,91 @@
+//! Command line flags.
+
+use std::path::PathBuf;
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+
+pub const USAGE: &str = "\
+Usage: tamatui [--seed SEED] [--save | --no-save] [--record FILE]
+       tamatui --replay FILE
+
+Options:
+  --seed SEED     Hatch a new egg from SEED instead of continuing the saved pet,
+                  so the run can be repeated exactly. The saved pet is left
+                  alone unless --save is given too.
+  --save          Replace the save on exit, even after a seeded run
+  --no-save       Leave the save file alone on exit
+  --record FILE   Write every input to FILE so the session can be replayed
+  --replay FILE   Play a recorded session back without a terminal and check
+                  that it ends with the same pet
+  -h, --help      Show this help";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Seed for a fresh, unsaved world.
+    pub seed: Option<u64>,
+    /// Whether to write the save file on exit, if asked either way.
+    pub save: Option<bool>,
+    /// Where to record the session's inputs.
+    pub record: Option<PathBuf>,
+    /// A recording to play back instead of starting the game.
+    pub replay: Option<PathBuf>,
+    pub help: bool,
+}
+
+/// Reads the options from `args`, not counting the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or_else(|| eyre!("--seed needs a value"))?;
+                let seed = value
+                    .parse()
+                    .wrap_err_with(|| format!("--seed {value} is not a whole number"))?;
+                options.seed = Some(seed);
+            }
+            "--record" | "--replay" => {
+                let path = args.next().ok_or_else(|| eyre!("{arg} needs a file"))?;
+                let path = Some(PathBuf::from(path));
+                if arg == "--record" {
+                    options.record = path;
+                } else {
+                    options.replay = path;
+                }
+            }
+            "--save" => options.save = Some(true),
+            "--no-save" => options.save = Some(false),
+            "-h" | "--help" => options.help = true,
+            other => bail!("unknown option {other}\n\n{USAGE}"),
+        }
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Result<Options> {
+        parse(text.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn reads_the_flags() {
+        assert_eq!(args("").unwrap(), Options::default());
+        assert_eq!(args("--seed 42").unwrap().seed, Some(42));
+        assert!(args("--help").unwrap().help);
+        assert_eq!(args("--seed 3 --save").unwrap().save, Some(true));
+        assert_eq!(args("--no-save").unwrap().save, Some(false));
+        let options = args("--seed 1 --record run.jsonl").unwrap();
+        assert_eq!(options.record, Some(PathBuf::from("run.jsonl")));
+        assert!(args("--replay").is_err());
+        assert!(args("--seed").is_err());
+        assert!(args("--seed many").is_err());
+        assert!(args("--sneed 1").is_err());
+    }
+}
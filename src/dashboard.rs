@@ -0,0 +1,253 @@
+//! The status dashboard: recent history of the pet's stats and how to color them.
+
+use std::{cmp::Ordering, collections::VecDeque, time::Duration};
+
+use ratatui::style::Color;
+use tamatui_core::{
+    care::{CALL_HAPPINESS, CALL_HUNGER},
+    evolution::Quality,
+    mood::{HUNGRY_FROM, SAD_BELOW, SICK_BELOW},
+    pet::DROWSY_AT,
+    Pet,
+};
+
+/// Simulated time between history samples.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+/// Samples kept for each stat, enough for the last ten simulated minutes.
+const HISTORY_LEN: usize = 60;
+/// How many samples back a stat is compared with to find its trend.
+const TREND_SPAN: usize = 6;
+
+/// A stat that goes from 0 to 100 and gets a gauge on the dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    Hunger,
+    Happiness,
+    Health,
+    Energy,
+    Cleanliness,
+    Discipline,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 6] = [
+        Stat::Hunger,
+        Stat::Happiness,
+        Stat::Health,
+        Stat::Energy,
+        Stat::Cleanliness,
+        Stat::Discipline,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stat::Hunger => "Hunger",
+            Stat::Happiness => "Happy",
+            Stat::Health => "Health",
+            Stat::Energy => "Energy",
+            Stat::Cleanliness => "Clean",
+            Stat::Discipline => "Discipl",
+        }
+    }
+
+    /// Two letter name for the compact status line.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Stat::Hunger => "Hu",
+            Stat::Happiness => "Ha",
+            Stat::Health => "He",
+            Stat::Energy => "En",
+            Stat::Cleanliness => "Cl",
+            Stat::Discipline => "Di",
+        }
+    }
+
+    pub fn value(self, pet: &Pet) -> u32 {
+        match self {
+            Stat::Hunger => pet.hunger,
+            Stat::Happiness => pet.happiness,
+            Stat::Health => pet.health,
+            Stat::Energy => pet.energy,
+            Stat::Cleanliness => pet.cleanliness,
+            Stat::Discipline => pet.discipline,
+        }
+    }
+
+    /// Hunger is the only stat where less is better.
+    fn higher_is_better(self) -> bool {
+        self != Stat::Hunger
+    }
+
+    /// Values at which the stat turns worrying, then dangerous.
+    fn thresholds(self) -> (u32, u32) {
+        match self {
+            Stat::Hunger => (HUNGRY_FROM, CALL_HUNGER),
+            Stat::Happiness => (SAD_BELOW, CALL_HAPPINESS),
+            Stat::Health => (50, SICK_BELOW),
+            Stat::Energy => (30, DROWSY_AT),
+            Stat::Cleanliness => (60, 30),
+            Stat::Discipline => (50, 20),
+        }
+    }
+
+    /// Green, yellow or red depending on how close `value` is to trouble.
+    pub fn color(self, value: u32) -> Color {
+        let (warn, danger) = self.thresholds();
+        let past = |threshold| {
+            if self.higher_is_better() {
+                value <= threshold
+            } else {
+                value >= threshold
+            }
+        };
+        if past(danger) {
+            Color::Red
+        } else if past(warn) {
+            Color::Yellow
+        } else {
+            Color::Green
+        }
+    }
+}
+
+/// Which way a stat has been moving lately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+impl Trend {
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Trend::Rising => "▲",
+            Trend::Falling => "▼",
+            Trend::Steady => "=",
+        }
+    }
+
+    /// Green when `stat` is getting better, red when it's getting worse.
+    pub fn color(self, stat: Stat) -> Color {
+        match (self, stat.higher_is_better()) {
+            (Trend::Steady, _) => Color::Gray,
+            (Trend::Rising, true) | (Trend::Falling, false) => Color::Green,
+            _ => Color::Red,
+        }
+    }
+}
+
+/// Samples of every [`Stat`] taken every [`SAMPLE_INTERVAL`] of the pet's life.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    samples: VecDeque<[u32; Stat::ALL.len()]>,
+    /// Pet age at the last sample.
+    sampled_at: Duration,
+}
+
+impl History {
+    /// Takes a sample if enough time has passed since the last one.
+    pub fn record(&mut self, pet: &Pet) {
+        // A younger pet means a new one hatched, so the old history is meaningless
+        if pet.age < self.sampled_at {
+            self.samples.clear();
+        }
+        if !self.samples.is_empty() && pet.age < self.sampled_at + SAMPLE_INTERVAL {
+            return;
+        }
+        if self.samples.len() == HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples
+            .push_back(Stat::ALL.map(|stat| stat.value(pet)));
+        self.sampled_at = pet.age;
+    }
+
+    /// The recorded values of `stat`, oldest first.
+    pub fn series(&self, stat: Stat) -> Vec<u64> {
+        let index = Stat::ALL.iter().position(|&s| s == stat).unwrap_or(0);
+        self.samples
+            .iter()
+            .map(|sample| u64::from(sample[index]))
+            .collect()
+    }
+
+    /// How `stat` compares with its value [`TREND_SPAN`] samples ago.
+    pub fn trend(&self, stat: Stat, now: u32) -> Trend {
+        let series = self.series(stat);
+        let Some(&then) = series.get(series.len().saturating_sub(TREND_SPAN + 1)) else {
+            return Trend::Steady;
+        };
+        match u64::from(now).cmp(&then) {
+            Ordering::Greater => Trend::Rising,
+            Ordering::Less => Trend::Falling,
+            Ordering::Equal => Trend::Steady,
+        }
+    }
+}
+
+pub fn quality_color(quality: Quality) -> Color {
+    match quality {
+        Quality::Great => Color::Green,
+        Quality::Good => Color::Yellow,
+        Quality::Poor => Color::Red,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pet_at(secs: u64, hunger: u32) -> Pet {
+        let mut pet = Pet::new();
+        pet.age = Duration::from_secs(secs);
+        pet.hunger = hunger;
+        pet
+    }
+
+    #[test]
+    fn samples_once_per_interval() {
+        let mut history = History::default();
+        for secs in [0, 4, 9, 10, 15, 20] {
+            history.record(&pet_at(secs, secs as u32));
+        }
+        assert_eq!(history.series(Stat::Hunger), [0, 10, 20]);
+    }
+
+    #[test]
+    fn only_recent_samples_are_kept() {
+        let mut history = History::default();
+        for sample in 0..HISTORY_LEN as u64 + 5 {
+            history.record(&pet_at(sample * 10, sample as u32));
+        }
+        let series = history.series(Stat::Hunger);
+        assert_eq!(series.len(), HISTORY_LEN);
+        assert_eq!(series[0], 5);
+        assert_eq!(series[HISTORY_LEN - 1], HISTORY_LEN as u64 + 4);
+    }
+
+    #[test]
+    fn a_new_pet_starts_a_new_history() {
+        let mut history = History::default();
+        for sample in 0..10 {
+            history.record(&pet_at(sample * 10, 50));
+        }
+        history.record(&pet_at(0, 7));
+        assert_eq!(history.series(Stat::Hunger), [7]);
+    }
+
+    #[test]
+    fn trends_compare_with_a_while_ago() {
+        let mut history = History::default();
+        assert_eq!(history.trend(Stat::Hunger, 80), Trend::Steady);
+        for sample in 0..10 {
+            history.record(&pet_at(sample * 10, 30 + sample as u32));
+        }
+        // Six samples back from the newest, hunger was 33
+        assert_eq!(history.trend(Stat::Hunger, 40), Trend::Rising);
+        assert_eq!(history.trend(Stat::Hunger, 33), Trend::Steady);
+        assert_eq!(history.trend(Stat::Hunger, 20), Trend::Falling);
+        assert_eq!(Trend::Rising.color(Stat::Hunger), Color::Red);
+        assert_eq!(Trend::Rising.color(Stat::Happiness), Color::Green);
+    }
+}
@@ -0,0 +1,486 @@
+//! Key bindings.
+//!
+//! Every action has a default set of keys. They can be changed in
+//! `$XDG_CONFIG_HOME/tamatui/keymap.toml`, which maps action names to one key or a
+//! list of keys:
+//!
+//! ```toml
+//! quit = ["q", "ctrl+c"]
+//! feed = "e"
+//! move_up = ["w", "up"]
+//! ```
+//!
+//! A key is a single character or a name such as `up`, `enter`, `space`, `tab` or
+//! `f1`, optionally prefixed with `ctrl+`, `alt+` or `shift+`. Actions left out of
+//! the file keep their defaults, except for keys the file gives to something else.
+//! Unknown actions, unreadable keys and keys the file binds to more than one action
+//! are reported as warnings rather than stopping the game.
+
+use std::{collections::BTreeMap, fmt, fs, path::PathBuf};
+
+use color_eyre::{eyre::WrapErr, Result};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// Something a key can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Select,
+    Back,
+    Feed,
+    Play,
+    Clean,
+    Medicine,
+    Scold,
+    Praise,
+    Sleep,
+    NewEgg,
+    Pause,
+    Step,
+    Speed,
+    DayClock,
+    Bell,
+    NextScreen,
+    PreviousScreen,
+    Help,
+    Suspend,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 24] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Select,
+        Action::Back,
+        Action::Feed,
+        Action::Play,
+        Action::Clean,
+        Action::Medicine,
+        Action::Scold,
+        Action::Praise,
+        Action::Sleep,
+        Action::NewEgg,
+        Action::Pause,
+        Action::Step,
+        Action::Speed,
+        Action::DayClock,
+        Action::Bell,
+        Action::NextScreen,
+        Action::PreviousScreen,
+        Action::Help,
+        Action::Suspend,
+        Action::Quit,
+    ];
+
+    /// The name used for the action in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::Feed => "feed",
+            Action::Play => "play",
+            Action::Clean => "clean",
+            Action::Medicine => "medicine",
+            Action::Scold => "scold",
+            Action::Praise => "praise",
+            Action::Sleep => "sleep",
+            Action::NewEgg => "new_egg",
+            Action::Pause => "pause",
+            Action::Step => "step",
+            Action::Speed => "speed",
+            Action::DayClock => "day_clock",
+            Action::Bell => "bell",
+            Action::NextScreen => "next_screen",
+            Action::PreviousScreen => "previous_screen",
+            Action::Help => "help",
+            Action::Suspend => "suspend",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Select => "Choose or confirm",
+            Action::Back => "Close a menu or stop playing",
+            Action::Feed => "Feed",
+            Action::Play => "Play a game",
+            Action::Clean => "Clean up",
+            Action::Medicine => "Give medicine",
+            Action::Scold => "Scold",
+            Action::Praise => "Praise",
+            Action::Sleep => "Put to bed / wake up",
+            Action::NewEgg => "Hatch a new egg once the pet is gone",
+            Action::Pause => "Pause time",
+            Action::Step => "Step time while paused",
+            Action::Speed => "Change speed",
+            Action::DayClock => "Switch day clock",
+            Action::Bell => "Toggle the bell",
+            Action::NextScreen => "Next screen",
+            Action::PreviousScreen => "Previous screen",
+            Action::Help => "Show help",
+            Action::Suspend => "Suspend to the shell",
+            Action::Quit => "Quit",
+        }
+    }
+
+    fn default_keys(self) -> Vec<String> {
+        self.defaults().iter().map(|key| key.to_string()).collect()
+    }
+
+    fn defaults(self) -> &'static [&'static str] {
+        match self {
+            Action::MoveUp => &["k", "up"],
+            Action::MoveDown => &["j", "down"],
+            Action::MoveLeft => &["h", "left"],
+            Action::MoveRight => &["l", "right"],
+            Action::Select => &["enter"],
+            Action::Back => &["esc"],
+            Action::Feed => &["f"],
+            Action::Play => &["p"],
+            Action::Clean => &["c"],
+            Action::Medicine => &["m"],
+            Action::Scold => &["s"],
+            Action::Praise => &["g"],
+            Action::Sleep => &["z"],
+            Action::NewEgg => &["n"],
+            Action::Pause => &["space"],
+            Action::Step => &["."],
+            Action::Speed => &["t"],
+            Action::DayClock => &["d"],
+            Action::Bell => &["b"],
+            Action::NextScreen => &["tab"],
+            Action::PreviousScreen => &["shift+tab"],
+            Action::Help => &["?"],
+            Action::Suspend => &["ctrl+z"],
+            Action::Quit => &["q", "ctrl+c"],
+        }
+    }
+}
+
+/// A key together with the modifiers held down with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// The key pressed in `event`, ignoring shift where it's already part of the key.
+    pub fn of(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if !matches!(event.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers |= event.modifiers & KeyModifiers::SHIFT;
+        }
+        Self {
+            code: event.code,
+            modifiers,
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // "+" on its own, or as the last key of a chord
+        let name = if text.ends_with("++") || text == "+" {
+            parts.truncate(parts.len().saturating_sub(2));
+            "+"
+        } else {
+            parts.pop()?
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "enter" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => {
+                    let number = name.strip_prefix('f')?.parse().ok()?;
+                    if !(1..=12).contains(&number) {
+                        return None;
+                    }
+                    KeyCode::F(number)
+                }
+            },
+        };
+
+        // Shift is folded into the key itself where the terminal does the same
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Some(Self { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            code => write!(f, "{code}"),
+        }
+    }
+}
+
+/// A single key or a list of them, as written in the config file.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Which action each key triggers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Keymap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let (keymap, _) = Self::build(|action| (action.default_keys(), false));
+        keymap
+    }
+}
+
+impl Keymap {
+    pub fn action(&self, event: KeyEvent) -> Option<Action> {
+        let key = Key::of(event);
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|&(_, action)| action)
+    }
+
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = Key> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| *bound == action)
+            .map(|&(key, _)| key)
+    }
+
+    /// All the keys for `action`, for showing in help text.
+    pub fn describe(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).map(|key| key.to_string()).collect();
+        if keys.is_empty() {
+            "(unbound)".to_string()
+        } else {
+            keys.join(" / ")
+        }
+    }
+
+    /// The main key for `action`, for short hints.
+    pub fn hint(&self, action: Action) -> String {
+        self.keys(action)
+            .next()
+            .map_or_else(|| "?".to_string(), |key| key.to_string())
+    }
+
+    /// Reads a keymap from TOML, returning it along with any problems found.
+    pub fn from_toml(source: &str) -> Result<(Self, Vec<String>)> {
+        let table: BTreeMap<String, Keys> = toml::from_str(source)?;
+        let mut warnings = Vec::new();
+        for name in table.keys() {
+            if !Action::ALL.iter().any(|action| action.name() == name) {
+                warnings.push(format!("unknown action \"{name}\""));
+            }
+        }
+        let (keymap, mut conflicts) = Self::build(|action| match table.get(action.name()) {
+            Some(Keys::One(key)) => (vec![key.clone()], true),
+            Some(Keys::Many(keys)) => (keys.clone(), true),
+            None => (action.default_keys(), false),
+        });
+        warnings.append(&mut conflicts);
+        Ok((keymap, warnings))
+    }
+
+    /// Binds each action to the keys `keys` gives for it, along with whether they
+    /// were written in the config file rather than being the defaults.
+    ///
+    /// Written keys are bound first, so they take over from another action's
+    /// defaults without a fuss. A key written for more than one action stays with
+    /// the first one in [`Action::ALL`].
+    fn build(keys: impl Fn(Action) -> (Vec<String>, bool)) -> (Self, Vec<String>) {
+        // Each binding remembers whether it was written
+        let mut bindings: Vec<(Key, Action, bool)> = Vec::new();
+        let mut warnings = Vec::new();
+        for pass in [true, false] {
+            for action in Action::ALL {
+                let (texts, written) = keys(action);
+                if written != pass {
+                    continue;
+                }
+                for text in texts {
+                    let Some(key) = Key::parse(&text) else {
+                        warnings.push(format!("can't read key \"{text}\" for {}", action.name()));
+                        continue;
+                    };
+                    match bindings.iter().find(|(bound, ..)| *bound == key) {
+                        Some(&(_, other, _)) if other == action => {}
+                        // The config file gave a default key to something else
+                        Some(&(_, _, true)) if !written => {}
+                        Some(&(_, other, _)) => warnings.push(format!(
+                            "{key} is bound to both {} and {}, keeping {}",
+                            other.name(),
+                            action.name(),
+                            other.name()
+                        )),
+                        None => bindings.push((key, action, written)),
+                    }
+                }
+            }
+        }
+        for action in Action::ALL {
+            if !bindings.iter().any(|&(_, bound, _)| bound == action) {
+                warnings.push(format!("nothing is bound to {}", action.name()));
+            }
+        }
+        let bindings = bindings
+            .into_iter()
+            .map(|(key, action, _)| (key, action))
+            .collect();
+        (Self { bindings }, warnings)
+    }
+}
+
+/// Location of the keymap config file.
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("tamatui").join("keymap.toml"))
+}
+
+/// Loads the keymap from [`config_path`], or the defaults if there's no file.
+///
+/// Fails only if the file can't be read or isn't valid TOML.
+pub fn load() -> Result<(Keymap, Vec<String>)> {
+    let Some(path) = config_path().filter(|path| path.exists()) else {
+        return Ok((Keymap::default(), Vec::new()));
+    };
+    let source =
+        fs::read_to_string(&path).wrap_err_with(|| format!("reading {}", path.display()))?;
+    Keymap::from_toml(&source).wrap_err_with(|| format!("in {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        let (_, warnings) = Keymap::from_toml("").unwrap();
+        assert_eq!(warnings, Vec::<String>::new());
+        let keymap = Keymap::default();
+        let event = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action(event), Some(Action::Quit));
+        let event = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(event), Some(Action::PreviousScreen));
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        for text in [
+            "q", "Q", "?", "+", "ctrl++", "space", "ctrl+c", "alt+up", "f5",
+        ] {
+            let key = Key::parse(text).unwrap();
+            assert_eq!(Key::parse(&key.to_string()), Some(key), "{text}");
+        }
+        assert_eq!(Key::parse("shift+tab").unwrap().code, KeyCode::BackTab);
+        assert_eq!(Key::parse("f13"), None);
+        assert_eq!(Key::parse("hyper+x"), None);
+    }
+
+    #[test]
+    fn config_overrides_and_warns() {
+        let source = r#"
+            feed = "e"
+            play = ["e", "x"]
+            dance = "d"
+            clean = "nope"
+        "#;
+        let (keymap, warnings) = Keymap::from_toml(source).unwrap();
+        let press = |c| keymap.action(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        assert_eq!(press('e'), Some(Action::Feed));
+        assert_eq!(press('x'), Some(Action::Play));
+        assert_eq!(press('f'), None);
+        assert_eq!(warnings.len(), 4, "{warnings:?}");
+        assert!(warnings.iter().any(|w| w.contains("dance")));
+        assert!(warnings.iter().any(|w| w.contains("both feed and play")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("nothing is bound to clean")));
+        assert!(Keymap::from_toml("feed = [").is_err());
+    }
+
+    #[test]
+    fn written_keys_take_over_defaults() {
+        let (keymap, warnings) = Keymap::from_toml(r#"quit = "f""#).unwrap();
+        let press = |c| keymap.action(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        assert_eq!(press('f'), Some(Action::Quit));
+        assert_eq!(press('q'), None);
+        assert_eq!(warnings, ["nothing is bound to feed"]);
+        let (keymap, warnings) = Keymap::from_toml(r#"new_egg = "q""#).unwrap();
+        let event = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(event), Some(Action::NewEgg));
+        assert_eq!(warnings, Vec::<String>::new());
+    }
+}
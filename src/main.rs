@@ -1,208 +1,1112 @@
-//! # [Ratatui] Canvas example
+//! tamatui, a virtual pet that lives in the terminal.
 //!
-//! The latest version of this example is available in the [examples] folder in the repository.
-//!
-//! Please note that the examples are designed to be run against the `main` branch of the Github
-//! repository. This means that you may not be able to compile with the latest release version on
-//! crates.io, or the one that you have installed locally.
-//!
-//! See the [examples readme] for more information on finding examples that match the version of the
-//! library you are using.
+//! The simulation itself is in `tamatui-core`. This binary draws it with
+//! [Ratatui], turns keys and clicks into actions on the [`World`], and keeps the
+//! pet in a save file between sessions so it goes on living while the terminal is
+//! closed. See [`cli::USAGE`] for the command line flags.
 //!
 //! [Ratatui]: https://github.com/ratatui/ratatui
-//! [examples]: https://github.com/ratatui/ratatui/blob/main/examples
-//! [examples readme]: https://github.com/ratatui/ratatui/blob/main/examples/README.md
 
-use std::time::{Duration, Instant};
+use std::{
+    cell::Cell,
+    collections::BTreeSet,
+    env,
+    io::{self, Write},
+    mem,
+    ops::ControlFlow,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use color_eyre::{eyre::bail, Result};
-use ratatui::{prelude::Alignment,
-    crossterm::event::{self, Event, KeyCode},
-    layout::{Constraint, Layout, Rect},
-    style::{Color, Style},
+use rand::{Rng, SeedableRng};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
+    layout::{Constraint, Layout, Margin, Rect},
+    prelude::Alignment,
+    style::{Color, Modifier, Style},
     symbols::Marker,
-    widgets::{Paragraph,
-        canvas::{Canvas, Circle, Map, MapResolution, Rectangle},
-        Block, Widget,
+    text::{Line, Span},
+    widgets::{
+        canvas::Canvas, Block, Clear, Gauge, List, ListItem, ListState, Paragraph, Sparkline,
+        Widget, Wrap,
     },
     DefaultTerminal, Frame,
 };
+use tamatui_core::{
+    behavior::ItemKind,
+    clock::{Every, SimClock, TimeScale, STEP},
+    evolution::Form,
+    food::Food,
+    personality::{Misbehavior, Response},
+    play::{GameKind, Side},
+    shop::Product,
+    stage::DeathCause,
+    world::PLAYGROUND,
+    Event as WorldEvent, Pet, SimRng, World,
+};
+
+mod assets;
+mod cli;
+mod dashboard;
+mod keymap;
+mod replay;
+mod save;
+mod screens;
+#[cfg(test)]
+mod snapshots;
+mod sprite;
+mod terminal;
+mod ui;
+
+use assets::SpriteSet;
+use dashboard::{History, Stat};
+use keymap::{Action, Keymap};
+use replay::{Input, Recorder, Start, REPLAY_VERSION};
+use save::Saved;
+use screens::{Screen, Setting};
+use sprite::{Animation, PetSprite};
+use terminal::{Interrupt, Signals};
+use ui::{DayClock, Daylight};
+
+/// How often the screen is redrawn. The simulation advances by however much time
+/// actually passed between frames, so this doesn't affect how fast the pet ages.
+const TICK_RATE: Duration = Duration::from_millis(16);
+
+/// Real time between marker changes.
+const MARKER_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How many entries the journal keeps, across sessions too, and how many fit in the
+/// status pane.
+const NOTIFICATION_LIMIT: usize = 200;
+const NOTIFICATIONS_SHOWN: u16 = 5;
+
+/// Real time the call icon spends on, then off.
+const CALL_BLINK: Duration = Duration::from_millis(500);
+
+/// Real time the clean-up sweep takes to cross the playground.
+const SWEEP_TIME: Duration = Duration::from_millis(800);
+/// How close a click has to be to a toy to pick it up, in playground units.
+const GRAB_DISTANCE: f64 = 6.0;
+
+/// Terminals smaller than this get the compact layout, with the status squeezed
+/// into a single line.
+const COMPACT_WIDTH: u16 = 60;
+const COMPACT_HEIGHT: u16 = 20;
 
 fn main() -> Result<()> {
     color_eyre::install()?;
-    let terminal = ratatui::init();
-    let app_result = App::new().run(terminal);
-    ratatui::restore();
-    app_result
+    let options = cli::parse(env::args().skip(1))?;
+    if options.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    if let Some(path) = &options.replay {
+        let count = replay(path)?;
+        println!("Replayed {count} inputs, the world matches the recording");
+        return Ok(());
+    }
+    let (keymap, warnings) = keymap::load()?;
+    let seed = options.seed.unwrap_or_else(rand::random);
+    // Clicks are aimed at the pet's sprite, so a recording has to use the same
+    // sprites on every machine it's played back on
+    let sprites = if options.record.is_some() {
+        assets::built_in(assets::DEFAULT_SPECIES)?
+    } else {
+        assets::load(assets::DEFAULT_SPECIES)?
+    };
+    let mut app = App::new(sprites, keymap, seed);
+    app.keymap_path = keymap::config_path();
+    for warning in warnings {
+        app.notify(format!("Keymap: {warning}"));
+    }
+    let mut offline = Duration::ZERO;
+    match save::load()? {
+        // A seeded run starts from scratch, keeping only the forms already unlocked
+        Some(saved) if options.seed.is_some() => app.unlocked = saved.unlocked,
+        Some(saved) => {
+            offline = saved.offline;
+            app.resume(saved);
+        }
+        None => {}
+    }
+    if let Some(path) = &options.record {
+        app.recorder = Some(Recorder::create(path, app.start())?);
+    }
+    // Catching up never ends the session
+    let _ = app.input(Input::CatchUp(offline))?;
+    let mut signals = Signals::register()?;
+    let terminal = terminal::init()?;
+    // A panic is caught just long enough to save the pet
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| app.run(terminal, &mut signals)));
+    terminal::restore();
+    let recorded = match app.recorder.take() {
+        Some(recorder) => recorder.finish(&app.world),
+        None => Ok(()),
+    };
+    // Saved in the default playground so it loads the same at any terminal size
+    app.world.resize(PLAYGROUND);
+    // A seeded run is usually a one-off, so it doesn't replace the real pet unless asked to
+    let stored = if options.save.unwrap_or(options.seed.is_none()) {
+        save::store(&app.world, &app.unlocked, &app.notifications)
+    } else {
+        Ok(())
+    };
+    match outcome {
+        Ok(app_result) => stored.and(recorded).and(app_result),
+        Err(payload) => panic::resume_unwind(payload),
+    }
 }
 
+/// Plays a recorded session back and checks it ends with the recorded world,
+/// returning how many inputs there were.
+fn replay(path: &Path) -> Result<usize> {
+    let replay = replay::load(path)?;
+    let start = replay.start;
+    let sprites = assets::built_in(assets::DEFAULT_SPECIES)?;
+    let mut app = App::new(sprites, start.keymap, start.world.seed);
+    app.world = start.world;
+    let count = replay.inputs.len();
+    for (number, (at, input)) in replay.inputs.into_iter().enumerate() {
+        // Drifting apart in time means the rules changed since the recording
+        if app.clock.simulated != at {
+            bail!(
+                "input {} came at {} simulated time in the recording, but at {} in the replay",
+                number + 1,
+                clock_time(at),
+                clock_time(app.clock.simulated)
+            );
+        }
+        if app.apply(input).is_break() {
+            break;
+        }
+    }
+    replay::check(&replay.end, &app.world)?;
+    Ok(count)
+}
+
+/// The terminal frontend: a view over a [`World`] plus the state that only matters on screen.
 struct App {
-    pet_position: (f64, f64), // x, y coordinates of our pet
-    playground: Rect,
-    hunger: u32, // A simple metric for the pet's needs
-    happiness: u32,
-    tick_count: u64,
+    world: World,
+    clock: SimClock,             // Speed and pausing, plus simulated versus real time
+    menu: Option<(Menu, usize)>, // Open menu and its selected entry
+    message: Option<String>,     // Feedback for the last action
     marker: Marker,
+    marker_timer: Every,
+    animation_time: Duration, // Real time driving the pet's idle animation
+    sweep: Option<(Duration, Vec<(f64, f64)>)>, // Clean-up in progress and the piles being swept
+    day_clock: DayClock,
+    notifications: Vec<(Duration, String)>, // Pet age and text, oldest first
+    bell: bool,                             // Ring the terminal bell when the pet calls
+    ringing: bool, // The pet called with the bell on, rung once the frame is done
+    unlocked: BTreeSet<Form>, // Forms any pet has reached so far
+    screen: Screen,
+    show_help: bool,      // Key binding overlay
+    shop_selected: usize, // Highlighted product in the shop
+    settings_selected: usize,
+    journal_scroll: u16, // Entries scrolled past in the journal, newest first
+    history: History,
+    keymap: Keymap,
+    keymap_path: Option<PathBuf>, // Where the keymap can be edited, shown in the settings
+    canvas: Cell<Rect>,           // Inside of the playground as last drawn, for mapping clicks
+    fitted: Rect,                 // Canvas the playground was last shaped for
+    dragging: Option<usize>,      // Item being dragged with the mouse
+    suspending: bool,             // Asked to suspend, which happens once the key is handled
+    recorder: Option<Recorder>,
+    sprites: SpriteSet,
 }
 impl App {
-    fn new() -> Self {
+    fn new(sprites: SpriteSet, keymap: Keymap, seed: u64) -> Self {
         Self {
-            pet_position: (100.0, 50.0), // Start in the middle of the playground
-            playground: Rect::new(10, 10, 200, 100),
-            hunger: 0,
-            happiness: 100,
-            tick_count: 0,
+            world: World::new(Pet::new(), seed),
+            clock: SimClock::default(),
+            menu: None,
+            message: None,
             marker: Marker::Braille, // Start with Braille for detailed representation
+            marker_timer: Every::default(),
+            animation_time: Duration::ZERO,
+            sweep: None,
+            day_clock: DayClock::default(),
+            notifications: Vec::new(),
+            bell: false,
+            ringing: false,
+            unlocked: BTreeSet::new(),
+            screen: Screen::Pet,
+            show_help: false,
+            shop_selected: 0,
+            settings_selected: 0,
+            journal_scroll: 0,
+            history: History::default(),
+            keymap,
+            keymap_path: None,
+            canvas: Cell::new(Rect::default()),
+            fitted: Rect::default(),
+            dragging: None,
+            suspending: false,
+            recorder: None,
+            sprites,
+        }
+    }
+
+    /// Continues with a saved pet. The time it spent offline is caught up on
+    /// separately, with [`Input::CatchUp`].
+    fn resume(&mut self, saved: Saved) {
+        let seed = self.world.seed;
+        self.world = saved.world;
+        // Saves from before seeds were kept get a fresh one
+        if !saved.seeded {
+            self.world.seed = seed;
+            self.world.rng = SimRng::seed_from_u64(seed);
         }
+        self.unlocked = saved.unlocked;
+        // Anything said before the save was read comes after the saved journal
+        let mut journal = saved.journal;
+        journal.append(&mut self.notifications);
+        let excess = journal.len().saturating_sub(NOTIFICATION_LIMIT);
+        journal.drain(..excess);
+        self.notifications = journal;
     }
 
-    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
-    let tick_rate = Duration::from_millis(16);
-    let mut last_tick = Instant::now();
-    loop {
-        terminal.draw(|frame| self.draw(frame))?;
-        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-        if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => break Ok(()),
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        self.pet_position.1 += 1.0;
-                        // Ensure the pet doesn't move out of the playground
-                        self.pet_position.1 = self.pet_position.1.min(self.playground.bottom() as f64 - 5.0);
-                    },
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        self.pet_position.1 -= 1.0;
-                        self.pet_position.1 = self.pet_position.1.max(self.playground.top() as f64);
-                    },
-                    KeyCode::Right | KeyCode::Char('l') => {
-                        self.pet_position.0 += 1.0;
-                        self.pet_position.0 = self.pet_position.0.min(self.playground.right() as f64 - 5.0);
-                    },
-                    KeyCode::Left | KeyCode::Char('h') => {
-                        self.pet_position.0 -= 1.0;
-                        self.pet_position.0 = self.pet_position.0.max(self.playground.left() as f64);
-                    },
-                    _ => {}
+    /// Everything a recording of this session has to start from.
+    fn start(&self) -> Start {
+        Start {
+            version: REPLAY_VERSION,
+            world: self.world.clone(),
+            keymap: self.keymap.clone(),
+        }
+    }
+
+    pub fn run(&mut self, mut terminal: DefaultTerminal, signals: &mut Signals) -> Result<()> {
+        let tick_rate = TICK_RATE;
+        let mut last_tick = Instant::now();
+        loop {
+            for interrupt in signals.pending() {
+                match interrupt {
+                    Interrupt::Quit => return Ok(()),
+                    Interrupt::Suspend => terminal::suspend(&mut terminal)?,
+                    Interrupt::Resume => terminal::resume(&mut terminal)?,
                 }
             }
+            terminal.draw(|frame| self.draw(frame))?;
+            // The playground's shape follows the canvas, which is only known once drawn
+            let canvas = self.canvas.get();
+            if canvas != self.fitted {
+                if self.input(Input::Canvas(canvas))?.is_break() {
+                    break Ok(());
+                }
+                continue;
+            }
+            let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+            if event::poll(timeout)? {
+                let input = match event::read()? {
+                    Event::Key(key) => Some(Input::Key(key)),
+                    // Only clicks and drags do anything, so plain moves aren't worth recording
+                    Event::Mouse(mouse) if mouse.kind != MouseEventKind::Moved => {
+                        Some(Input::Mouse(mouse))
+                    }
+                    Event::Resize(..) => {
+                        terminal.autoresize()?;
+                        None
+                    }
+                    _ => None,
+                };
+                if let Some(input) = input {
+                    if self.input(input)?.is_break() {
+                        break Ok(());
+                    }
+                }
+                if mem::take(&mut self.suspending) {
+                    terminal::suspend(&mut terminal)?;
+                }
+            }
+
+            let elapsed = last_tick.elapsed();
+            if elapsed >= tick_rate {
+                last_tick = Instant::now();
+                if self.input(Input::Tick(elapsed))?.is_break() {
+                    break Ok(());
+                }
+            }
+            if mem::take(&mut self.ringing) {
+                // Best effort: a missing bell isn't worth interrupting the game over
+                let _ = io::stdout()
+                    .write_all(b"\x07")
+                    .and_then(|()| io::stdout().flush());
+            }
         }
+    }
 
-        if last_tick.elapsed() >= tick_rate {
-            self.on_tick();
-            last_tick = Instant::now();
+    /// Records `input` if the session is being recorded, then acts on it.
+    fn input(&mut self, input: Input) -> Result<ControlFlow<()>> {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(self.clock.simulated, input)?;
         }
+        Ok(self.apply(input))
     }
-}
 
-    fn on_tick(&mut self) {
-    self.tick_count += 1;
+    /// Acts on `input`. Everything that changes the world goes through here, so a
+    /// replay that feeds in the same inputs ends up in the same place.
+    fn apply(&mut self, input: Input) -> ControlFlow<()> {
+        match input {
+            Input::Key(key) => return self.handle_key(key),
+            Input::Mouse(mouse) => self.handle_mouse(mouse),
+            Input::Tick(elapsed) => self.on_tick(elapsed),
+            Input::CatchUp(offline) => {
+                for event in self.world.catch_up(offline) {
+                    self.announce(event);
+                }
+                self.unlocked.extend(self.world.pet.form);
+            }
+            Input::Canvas(area) => {
+                self.canvas.set(area);
+                self.fit_playground(area);
+            }
+        }
+        ControlFlow::Continue(())
+    }
 
-    // Increase hunger over time
-    if self.tick_count % 60 == 0 { // Roughly every second if tick_rate is 16ms
-        self.hunger = (self.hunger + 1).min(100);
+    /// Reshapes the playground to match a canvas drawn at `area`.
+    fn fit_playground(&mut self, area: Rect) {
+        self.fitted = area;
+        if !area.is_empty() {
+            self.world.resize(ui::playground_for(area));
+        }
     }
 
-    // Decrease happiness over time
-    if self.tick_count % 120 == 0 {
-        self.happiness = self.happiness.saturating_sub(1);
+    /// Routes a key to the help overlay, the game or menu in progress, or the current screen.
+    fn handle_key(&mut self, key: KeyEvent) -> ControlFlow<()> {
+        let action = self.keymap.action(key);
+        if self.show_help {
+            self.show_help = false;
+            return ControlFlow::Continue(());
+        }
+        if action == Some(Action::Help) {
+            self.show_help = true;
+        } else if action == Some(Action::Suspend) {
+            self.suspending = true;
+        } else if self.world.game().is_some() {
+            self.game_key(key.code, action);
+        } else if let Some((menu, selected)) = self.menu {
+            self.handle_menu_key(menu, key.code, action, selected);
+        } else {
+            match (action, key.code) {
+                (Some(Action::Quit), _) => return ControlFlow::Break(()),
+                (Some(Action::NextScreen), _) => self.screen = self.screen.next(),
+                (Some(Action::PreviousScreen), _) => self.screen = self.screen.previous(),
+                (Some(Action::Pause), _) => {
+                    self.clock.toggle_pause();
+                    let message = if self.clock.paused {
+                        "Paused"
+                    } else {
+                        "Resumed"
+                    };
+                    self.message = Some(message.to_string());
+                }
+                (Some(Action::Step), _) => {
+                    self.clock.step();
+                    self.message = Some(format!("Stepped {}s", STEP.as_secs()));
+                }
+                (None, KeyCode::Char(c @ '1'..='9')) => {
+                    if let Some(&screen) = Screen::ALL.get(c as usize - '1' as usize) {
+                        self.screen = screen;
+                    }
+                }
+                _ => match self.screen {
+                    Screen::Pet => self.pet_key(action),
+                    Screen::Shop => self.shop_key(action),
+                    Screen::Journal => self.journal_key(key.code, action),
+                    Screen::Settings => self.settings_key(key.code, action),
+                    Screen::Stats | Screen::Evolution => {}
+                },
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Clicks and drags in the playground. Anything covering it, like a menu or a
+    /// game, swallows the mouse.
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let covered = self.show_help || self.menu.is_some() || self.world.game().is_some();
+        if self.screen != Screen::Pet || covered || self.world.pet.is_dead() {
+            self.dragging = None;
+            return;
+        }
+        let spot = ui::to_playground(
+            self.canvas.get(),
+            self.world.playground,
+            mouse.column,
+            mouse.row,
+        );
+        match (mouse.kind, spot) {
+            (MouseEventKind::Down(MouseButton::Left), Some(spot)) => self.click(spot),
+            (MouseEventKind::Drag(MouseButton::Left), Some(spot)) => {
+                if let Some(index) = self.dragging {
+                    self.world.move_item(index, spot);
+                }
+            }
+            (MouseEventKind::Up(MouseButton::Left), _) => self.dragging = None,
+            _ => {}
+        }
     }
 
-    // Update marker for visual change
-    if (self.tick_count % 180) == 0 {
-        self.marker = match self.marker {
-            Marker::Dot => Marker::Braille,
-            Marker::Braille => Marker::Block,
-            Marker::Block => Marker::HalfBlock,
-            Marker::HalfBlock => Marker::Bar,
-            Marker::Bar => Marker::Dot,
+    /// Picks up a toy, pets the pet or drops a meal, depending on what's at `spot`.
+    fn click(&mut self, spot: (f64, f64)) {
+        self.dragging = self.world.items.iter().position(|item| {
+            item.kind == ItemKind::Toy
+                && (item.position.0 - spot.0).hypot(item.position.1 - spot.1) <= GRAB_DISTANCE
+        });
+        if self.dragging.is_some() {
+            return;
+        }
+        let result = if sprite::touches(&self.world.pet, &self.sprites, spot) {
+            self.world.stroke().map(|enjoyed| {
+                if enjoyed {
+                    "Your pet loves being petted"
+                } else {
+                    "Your pet has had enough petting for now"
+                }
+            })
+        } else {
+            self.world
+                .drop_food(Food::Meal, spot)
+                .map(|()| "Dropped a meal")
         };
+        self.message = Some(match result {
+            Ok(message) => message.to_string(),
+            Err(refusal) => refusal.to_string(),
+        });
     }
 
-    // Simple pet movement logic (could be expanded for more complex behavior)
-    let (dx, dy) = (1.0, -0.5); // Example movement vector
-    let new_x = self.pet_position.0 + dx;
-    let new_y = self.pet_position.1 + dy;
+    fn game_key(&mut self, code: KeyCode, action: Option<Action>) {
+        match (action, code) {
+            (Some(Action::Back), _) => {
+                self.world.stop_game();
+                self.message = Some("Stopped playing".to_string());
+            }
+            (Some(Action::MoveLeft), _) => self.world.game_input(Side::Left),
+            (Some(Action::MoveRight), _) => self.world.game_input(Side::Right),
+            _ => {}
+        }
+    }
 
-    // Keep the pet within bounds
-    self.pet_position.0 = new_x.max(self.playground.left() as f64).min(self.playground.right() as f64 - 5.0);
-    self.pet_position.1 = new_y.max(self.playground.top() as f64).min(self.playground.bottom() as f64 - 5.0);
-}
-      fn pet_canvas(&self) -> impl Widget + '_ {
-    Canvas::default()
-        .block(Block::bordered().title("Tamagotchi"))
-        .marker(self.marker)
-        .paint(|ctx| {
-            // Draw the pet - this can be made more complex
-            ctx.draw(&Circle {
-                x: self.pet_position.0,
-                y: self.pet_position.1,
-                radius: 5.0,
-                color: Color::Yellow,
-            });
-            // Maybe add eyes or a smile to indicate mood
-        })
-        .x_bounds([10.0, 210.0])
-        .y_bounds([10.0, 110.0])
-}
+    fn pet_key(&mut self, action: Option<Action>) {
+        if self.world.pet.is_dead() {
+            if action == Some(Action::NewEgg) {
+                // The owner's savings outlive the pet
+                let inventory = self.world.inventory.clone();
+                // Drawn from the old world, so one seed covers the whole session
+                let seed = self.world.rng.random();
+                let playground = self.world.playground;
+                self.world = World::new(Pet::new(), seed);
+                self.world.resize(playground);
+                self.world.inventory = inventory;
+                self.message = None;
+            }
+            return;
+        }
+        let Some(action) = action else {
+            return;
+        };
+        match action {
+            Action::Feed => self.menu = Some((Menu::Feed, 0)),
+            Action::Play => self.menu = Some((Menu::Play, 0)),
+            Action::Scold | Action::Praise => {
+                let response = if action == Action::Scold {
+                    Response::Scold
+                } else {
+                    Response::Praise
+                };
+                self.message = Some(match self.world.respond(response) {
+                    Ok(reaction) => reaction.to_string(),
+                    Err(refusal) => refusal.to_string(),
+                });
+            }
+            Action::Clean => {
+                let piles = self.world.pet.waste.clone();
+                self.message = Some(match self.world.clean() {
+                    Ok(_) => {
+                        self.sweep = Some((Duration::ZERO, piles));
+                        "All clean!".to_string()
+                    }
+                    Err(refusal) => refusal.to_string(),
+                });
+            }
+            Action::Medicine => {
+                self.message = Some(match self.world.give_medicine() {
+                    Ok(illness) => format!("Cured the {}", illness.name().to_lowercase()),
+                    Err(refusal) => refusal.to_string(),
+                });
+            }
+            Action::Sleep => {
+                let result = if self.world.pet.asleep {
+                    self.world.wake().map(|()| "Woke your pet up. Grumble...")
+                } else {
+                    self.world.sleep().map(|()| "Tucked your pet in")
+                };
+                self.message = Some(match result {
+                    Ok(message) => message.to_string(),
+                    Err(refusal) => refusal.to_string(),
+                });
+            }
+            Action::Bell => self.change(Setting::Bell),
+            Action::DayClock => self.change(Setting::DayClock),
+            Action::Speed => self.change(Setting::Speed),
+            // The playground's y axis points up
+            Action::MoveDown => self.world.move_pet(0.0, -1.0),
+            Action::MoveUp => self.world.move_pet(0.0, 1.0),
+            Action::MoveRight => self.world.move_pet(1.0, 0.0),
+            Action::MoveLeft => self.world.move_pet(-1.0, 0.0),
+            Action::Select
+            | Action::Back
+            | Action::NewEgg
+            | Action::Pause
+            | Action::Step
+            | Action::NextScreen
+            | Action::PreviousScreen
+            | Action::Help
+            | Action::Suspend
+            | Action::Quit => {}
+        }
+    }
 
-fn draw(&self, frame: &mut Frame) {
-        let sizes = Layout::horizontal([
-            Constraint::Percentage(30), // Smaller percentage for status
-            Constraint::Percentage(70), // Larger for pet area
-        ]).split(frame.area());
-        let [status, pet_area] = *sizes else { todo!() };
+    /// Shows `text` as the latest message and adds it to the notification list.
+    fn notify(&mut self, text: String) {
+        if self.notifications.len() == NOTIFICATION_LIMIT {
+            self.notifications.remove(0);
+        }
+        self.notifications.push((self.world.pet.age, text.clone()));
+        self.message = Some(text);
+    }
 
+    /// Tells the owner about `event`, along with the key that deals with it.
+    fn announce(&mut self, event: WorldEvent) {
+        let action = match event {
+            WorldEvent::Misbehaved(Misbehavior::Fussy) => Some(Action::Scold),
+            WorldEvent::MadeAMess => Some(Action::Clean),
+            WorldEvent::FellIll(_) => Some(Action::Medicine),
+            _ => None,
+        };
+        let text = match action {
+            Some(action) => format!("{event} ({}: {})", self.keymap.hint(action), action.name()),
+            None => event.to_string(),
+        };
+        self.notify(text);
+    }
 
-        frame.render_widget(self.status_canvas(), status);
-        frame.render_widget(self.pet_canvas(), pet_area);
+    fn on_tick(&mut self, elapsed: Duration) {
+        // Mini-games run in real time; only the pet's life is fast-forwarded
+        let dt = self.clock.advance(elapsed, self.world.game().is_none());
+        for event in self.world.step(dt) {
+            if self.bell && matches!(event, WorldEvent::Called(_)) {
+                self.ringing = true;
+            }
+            self.announce(event);
+        }
+        self.unlocked.extend(self.world.pet.form);
+        self.history.record(&self.world.pet);
+        if self.world.pet.is_dead() {
+            self.menu = None;
+        }
+
+        self.animation_time += elapsed;
+        if let Some((time, _)) = &mut self.sweep {
+            *time += elapsed;
+            if *time >= SWEEP_TIME {
+                self.sweep = None;
+            }
+        }
+
+        // Update marker for visual change
+        for _ in 0..self.marker_timer.tick(elapsed, MARKER_INTERVAL) {
+            self.marker = match self.marker {
+                Marker::Dot => Marker::Braille,
+                Marker::Braille => Marker::Block,
+                Marker::Block => Marker::HalfBlock,
+                Marker::HalfBlock => Marker::Bar,
+                Marker::Bar => Marker::Dot,
+            };
+        }
+    }
+
+    fn handle_menu_key(
+        &mut self,
+        menu: Menu,
+        code: KeyCode,
+        action: Option<Action>,
+        selected: usize,
+    ) {
+        let len = menu.len();
+        match (action, code) {
+            (Some(Action::Quit | Action::Back), _) => self.menu = None,
+            (Some(Action::MoveDown), _) => self.menu = Some((menu, (selected + 1) % len)),
+            (Some(Action::MoveUp), _) => self.menu = Some((menu, (selected + len - 1) % len)),
+            (Some(Action::Select), _) => self.choose(menu, selected),
+            (_, KeyCode::Char(c @ '1'..='9')) if (c as usize - '1' as usize) < len => {
+                self.choose(menu, c as usize - '1' as usize);
+            }
+            _ => {}
+        }
     }
 
-fn status_canvas(&self) -> impl Widget {
-    let text = vec![
-        format!("Hunger: {}", self.hunger),
-        format!("Happiness: {}", self.happiness),
-    ];
-    Paragraph::new(text.join("\n"))
-        .block(Block::bordered().title("Status").style(Style::default().fg(Color::White)))
-        .style(Style::default().fg(Color::White))
-        .alignment(Alignment::Center)
-}    fn boxes_canvas(&self, area: Rect) -> impl Widget {
-        let left = 0.0;
-        let right = f64::from(area.width);
-        let bottom = 0.0;
-        let top = f64::from(area.height).mul_add(2.0, -4.0);
+    fn choose(&mut self, menu: Menu, index: usize) {
+        self.menu = None;
+        let message = match menu {
+            Menu::Feed => match self.world.feed(Food::ALL[index]) {
+                Ok(fed) => fed.to_string(),
+                Err(refusal) => refusal.to_string(),
+            },
+            Menu::Play => {
+                let kind = GameKind::ALL[index];
+                match self.world.start_game(kind) {
+                    Ok(()) => format!(
+                        "Playing {} ({} to stop)",
+                        kind.name(),
+                        self.keymap.hint(Action::Back)
+                    ),
+                    Err(refusal) => refusal.to_string(),
+                }
+            }
+        };
+        self.message = Some(message);
+    }
+
+    fn pet_canvas(&self) -> impl Widget + '_ {
+        let title = self
+            .world
+            .game()
+            .map_or("Tamagotchi", |game| game.kind().name());
+        // Games are laid out for the default playground
+        let playground = if self.world.game().is_some() {
+            PLAYGROUND
+        } else {
+            self.world.playground
+        };
+        let daylight = self.daylight();
         Canvas::default()
-            .block(Block::bordered().title("Rects"))
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .style(Style::default().fg(daylight.frame())),
+            )
+            .background_color(daylight.sky())
             .marker(self.marker)
-            .x_bounds([left, right])
-            .y_bounds([bottom, top])
             .paint(|ctx| {
-                for i in 0..=11 {
-                    ctx.draw(&Rectangle {
-                        x: f64::from(i * i + 3 * i) / 2.0 + 2.0,
-                        y: 2.0,
-                        width: f64::from(i),
-                        height: f64::from(i),
-                        color: Color::Red,
-                    });
-                    ctx.draw(&Rectangle {
-                        x: f64::from(i * i + 3 * i) / 2.0 + 2.0,
-                        y: 21.0,
-                        width: f64::from(i),
-                        height: f64::from(i),
-                        color: Color::Blue,
-                    });
+                if let Some(game) = self.world.game() {
+                    ui::paint_game(ctx, game, &self.keymap);
+                    return;
                 }
-                for i in 0..100 {
-                    if i % 10 != 0 {
-                        ctx.print(f64::from(i) + 1.0, 0.0, format!("{i}", i = i % 10));
+                ui::paint_items(ctx, &self.world.items);
+                ui::paint_waste(ctx, &self.world.pet.waste);
+                if let Some((time, piles)) = &self.sweep {
+                    let progress = time.as_secs_f64() / SWEEP_TIME.as_secs_f64();
+                    ui::paint_sweep(ctx, self.world.playground, progress, piles);
+                }
+                let animation = Animation::at(self.animation_time);
+                let pet = &self.world.pet;
+                ctx.draw(&PetSprite::new(pet, animation, &self.sprites));
+                if pet.asleep {
+                    ctx.layer();
+                    ctx.print(pet.position.0 + 12.0, pet.position.1 + 15.0, "z Z");
+                }
+            })
+            .x_bounds([playground.left, playground.right])
+            .y_bounds([playground.bottom, playground.top])
+    }
+
+    fn daylight(&self) -> Daylight {
+        Daylight::at(self.day_clock.hour(self.world.pet.age))
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        // Only a playground drawn this frame can be clicked
+        self.canvas.set(Rect::default());
+        let [tabs, body] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(frame.area());
+        frame.render_widget(self.tabs(tabs.width), tabs);
+        match self.screen {
+            Screen::Pet => self.render_pet_screen(frame, body),
+            Screen::Stats => self.render_stats_screen(frame, body),
+            Screen::Shop => self.render_shop(frame, body),
+            Screen::Journal => frame.render_widget(self.journal(), body),
+            Screen::Evolution => frame.render_widget(self.evolution_tree(), body),
+            Screen::Settings => self.render_settings(frame, body),
+        }
+        if self.show_help {
+            self.render_help(frame, body);
+        }
+    }
+
+    fn render_pet_screen(&self, frame: &mut Frame, area: Rect) {
+        let terminal = frame.area();
+        if terminal.width < COMPACT_WIDTH || terminal.height < COMPACT_HEIGHT {
+            let [status, pet_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+            frame.render_widget(self.status_line(), status);
+            self.render_playground(frame, pet_area);
+            return;
+        }
+        let [status, pet_area] = Layout::horizontal([
+            Constraint::Percentage(30), // Smaller percentage for status
+            Constraint::Percentage(70), // Larger for pet area
+        ])
+        .areas(area);
+        let [status, notifications] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(NOTIFICATIONS_SHOWN + 2),
+        ])
+        .areas(status);
+
+        self.render_status(frame, status);
+        frame.render_widget(self.notification_list(), notifications);
+        self.render_playground(frame, pet_area);
+    }
+
+    /// The canvas with the pet, its menu on top, or the memorial once it's gone.
+    fn render_playground(&self, frame: &mut Frame, pet_area: Rect) {
+        if let Some(cause) = self.world.pet.cause_of_death {
+            frame.render_widget(self.memorial(cause), pet_area);
+            return;
+        }
+        frame.render_widget(self.pet_canvas(), pet_area);
+        self.canvas.set(pet_area.inner(Margin::new(1, 1)));
+        if let Some((menu, selected)) = self.menu {
+            self.render_menu(frame, pet_area, menu, selected);
+        }
+    }
+
+    fn render_menu(&self, frame: &mut Frame, area: Rect, menu: Menu, selected: usize) {
+        let items: Vec<ListItem> = match menu {
+            Menu::Feed => Food::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, food)| {
+                    let effect = food.effect();
+                    let mut line = format!(
+                        "{} {:<6} hunger {:+} happy {:+}",
+                        i + 1,
+                        food.name(),
+                        effect.hunger,
+                        effect.happiness
+                    );
+                    let treats = self.world.inventory.count(Product::Treat);
+                    if *food == Food::Treat {
+                        line += &format!(" x{treats}");
                     }
-                    if i % 2 == 0 && i % 10 != 0 {
-                        ctx.print(0.0, f64::from(i), format!("{i}", i = i % 10));
+                    let available = *food != Food::Treat || treats > 0;
+                    if available && self.world.pet.cooldown_remaining(*food).is_zero() {
+                        ListItem::new(line)
+                    } else {
+                        ListItem::new(line).style(Style::default().fg(Color::DarkGray))
                     }
-                }
+                })
+                .collect(),
+            Menu::Play => GameKind::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, kind)| ListItem::new(format!("{} {}", i + 1, kind.name())))
+                .collect(),
+        };
+        let list = List::new(items)
+            .block(Block::bordered().title(menu.title(&self.keymap)))
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Yellow));
+        let popup = centered(area, 42, menu.len() as u16 + 2);
+        frame.render_widget(Clear, popup);
+        frame.render_stateful_widget(
+            list,
+            popup,
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    fn memorial(&self, cause: DeathCause) -> impl Widget {
+        let pet = &self.world.pet;
+        let text = [
+            String::new(),
+            "~ In loving memory ~".to_string(),
+            String::new(),
+            cause.describe().to_string(),
+            format!(
+                "Lived {}h {}m",
+                pet.age.as_secs() / 3600,
+                pet.age.as_secs() / 60 % 60
+            ),
+            String::new(),
+            format!("Final hunger: {}", pet.hunger),
+            format!("Final happiness: {}", pet.happiness),
+            format!("Final health: {}", pet.health),
+            format!("Final weight: {}g", pet.weight),
+            format!("Form: {}", pet.form.map_or("Egg", |form| form.name())),
+            // Traits stay hidden until the very end
+            format!(
+                "Personality: {}",
+                pet.traits
+                    .iter()
+                    .map(|t| t.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            String::new(),
+            format!(
+                "{}: hatch a new egg  {}: quit",
+                self.keymap.hint(Action::NewEgg),
+                self.keymap.hint(Action::Quit)
+            ),
+        ];
+        Paragraph::new(text.join("\n"))
+            .block(Block::bordered().title("Memorial"))
+            .style(Style::default().fg(Color::Gray))
+            .alignment(Alignment::Center)
+    }
+
+    fn notification_list(&self) -> impl Widget {
+        let items: Vec<ListItem> = self
+            .notifications
+            .iter()
+            .rev()
+            .take(NOTIFICATIONS_SHOWN as usize)
+            .map(|(age, text)| {
+                let minutes = age.as_secs() / 60;
+                ListItem::new(format!("{}h{:02} {text}", minutes / 60, minutes % 60))
             })
+            .collect();
+        List::new(items).block(Block::bordered().title(if self.bell {
+            "Notifications (bell on)"
+        } else {
+            "Notifications"
+        }))
+    }
+
+    /// A blinking banner while the pet is calling for its owner.
+    fn call_banner(&self) -> Line<'static> {
+        let pet = &self.world.pet;
+        let reason = match (pet.call.filter(|call| call.is_open()), pet.misbehaving) {
+            (Some(call), _) => call.need.name(),
+            // Acting up looks just like a real call
+            (None, Some(Misbehavior::FakeCall)) => "Attention",
+            _ => return Line::default(),
+        };
+        let blink_on = (self.animation_time.as_millis() / CALL_BLINK.as_millis()).is_multiple_of(2);
+        if !blink_on {
+            return Line::default();
+        }
+        Line::styled(
+            format!("(!) {reason} (!)"),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    /// Everything the status panel says that fits on one line: the call banner or
+    /// stage, each stat, and the last message.
+    fn status_line(&self) -> Line<'static> {
+        let pet = &self.world.pet;
+        let banner = self.call_banner();
+        let mut spans = if banner.spans.is_empty() {
+            vec![Span::styled(
+                pet.stage().name(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )]
+        } else {
+            banner.spans
+        };
+        if self.clock.paused || self.clock.scale != TimeScale::X1 {
+            spans.push(Span::raw(format!(" {}", self.speed())));
+        }
+        for stat in Stat::ALL {
+            let value = stat.value(pet);
+            spans.push(Span::styled(
+                format!(" {}{value}", stat.short_name()),
+                Style::default().fg(stat.color(value)),
+            ));
+        }
+        if let Some(message) = &self.message {
+            spans.push(Span::raw(format!("  {message}")));
+        }
+        Line::from(spans)
+    }
+
+    /// How fast time is running, or that it's stopped.
+    fn speed(&self) -> String {
+        if self.clock.paused {
+            "Paused".to_string()
+        } else {
+            format!("{}x", self.clock.scale.factor())
+        }
+    }
+
+    fn render_status(&self, frame: &mut Frame, area: Rect) {
+        let pet = &self.world.pet;
+        let block = Block::bordered()
+            .title("Status")
+            .style(Style::default().fg(Color::White));
+        let [banner, info, stats, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(7),
+            Constraint::Length(Stat::ALL.len() as u16),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+        frame.render_widget(block, area);
+        frame.render_widget(
+            Paragraph::new(self.call_banner()).alignment(Alignment::Center),
+            banner,
+        );
+
+        let quality = pet.care.quality();
+        let mood = match pet.illness {
+            Some(illness) => format!("{} ({})", pet.mood().name(), illness.name()),
+            None => pet.mood().name().to_string(),
+        };
+        let doing = if pet.asleep {
+            "Sleeping"
+        } else if let Some(misbehavior) = pet.misbehaving {
+            misbehavior.describe()
+        } else {
+            self.world.activity().name()
+        };
+        let info_lines = vec![
+            Line::from(format!(
+                "{} {}",
+                pet.stage().name(),
+                pet.form.map_or("", |form| form.name())
+            ))
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+            Line::from(format!("{mood}, {}", doing.to_lowercase())),
+            Line::from(format!(
+                "Age {}m  Weight {}g",
+                pet.age.as_secs() / 60,
+                pet.weight
+            )),
+            Line::from(vec![
+                Span::raw("Care "),
+                Span::styled(
+                    quality.name(),
+                    Style::default().fg(dashboard::quality_color(quality)),
+                ),
+                Span::raw(format!("  Mistakes {}", pet.care_mistakes)),
+            ]),
+            Line::from(format!(
+                "{}  {} ({})",
+                self.speed(),
+                self.daylight().name(),
+                self.day_clock.name()
+            )),
+            // One line each, since a long session wouldn't fit both in a narrow panel
+            Line::from(format!("Sim time  {}", clock_time(self.clock.simulated))),
+            Line::from(format!("Real time {}", clock_time(self.clock.real))),
+        ];
+        frame.render_widget(Paragraph::new(info_lines), info);
+
+        let rows = Layout::vertical(Stat::ALL.map(|_| Constraint::Length(1))).split(stats);
+        for (stat, row) in Stat::ALL.into_iter().zip(rows.iter()) {
+            self.render_stat(frame, *row, stat);
+        }
+
+        let text = [
+            String::new(),
+            self.message.clone().unwrap_or_default(),
+            String::new(),
+            [
+                (Action::Feed, "feed"),
+                (Action::Play, "play"),
+                (Action::Clean, "clean"),
+                (Action::Help, "help"),
+                (Action::NextScreen, "next screen"),
+                (Action::Quit, "quit"),
+            ]
+            .map(|(action, label)| format!("{}: {label}", self.keymap.hint(action)))
+            .join("  "),
+        ];
+        frame.render_widget(
+            Paragraph::new(text.join("\n"))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            footer,
+        );
+    }
+
+    /// A gauge with the stat's current value and trend, followed by its recent history.
+    fn render_stat(&self, frame: &mut Frame, area: Rect, stat: Stat) {
+        let value = stat.value(&self.world.pet);
+        let trend = self.history.trend(stat, value);
+        let color = stat.color(value);
+        let [gauge, arrow, sparkline] = Layout::horizontal([
+            Constraint::Percentage(55),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+        .areas(area);
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(color).bg(Color::DarkGray))
+                .ratio(f64::from(value.min(100)) / 100.0)
+                .label(format!("{} {value}", stat.name())),
+            gauge,
+        );
+        frame.render_widget(
+            Paragraph::new(trend.arrow())
+                .style(Style::default().fg(trend.color(stat)))
+                .alignment(Alignment::Center),
+            arrow,
+        );
+        // Only the most recent samples fit
+        let series = self.history.series(stat);
+        let shown = &series[series.len().saturating_sub(sparkline.width as usize)..];
+        frame.render_widget(
+            Sparkline::default()
+                .data(shown)
+                .max(100)
+                .style(Style::default().fg(color)),
+            sparkline,
+        );
     }
 }
 
+/// A popup menu listing the choices for an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Menu {
+    Feed,
+    Play,
+}
+
+impl Menu {
+    fn len(self) -> usize {
+        match self {
+            Menu::Feed => Food::ALL.len(),
+            Menu::Play => GameKind::ALL.len(),
+        }
+    }
+
+    fn title(self, keymap: &Keymap) -> String {
+        let (name, verb) = match self {
+            Menu::Feed => ("Feed", "eat"),
+            Menu::Play => ("Play", "start"),
+        };
+        format!(
+            "{name} ({} to {verb}, {} to close)",
+            keymap.hint(Action::Select),
+            keymap.hint(Action::Back)
+        )
+    }
+}
+
+/// `duration` as hours, minutes and seconds, like `1:02:03`.
+fn clock_time(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// A `width` x `height` rectangle centered in `area`, shrunk to fit if needed.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
@@ -0,0 +1,228 @@
+//! Recording a session's inputs and playing them back without a terminal.
+//!
+//! A replay file is JSON lines: a [`Start`] entry with everything the session
+//! began from, one entry per [`Input`] stamped with the simulated time it arrived
+//! at, and an end entry with the final world. Since all randomness comes from the
+//! world's seeded RNG, feeding the same inputs to the same start has to end with
+//! the same world, which [`check`] verifies.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use ratatui::{
+    crossterm::event::{KeyEvent, MouseEvent},
+    layout::Rect,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tamatui_core::World;
+
+use crate::keymap::Keymap;
+
+/// Bump this whenever the layout of [`Entry`] changes.
+pub const REPLAY_VERSION: u32 = 2;
+
+/// Something the frontend reacts to, in the order it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Input {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    /// Real time that passed between two frames.
+    Tick(Duration),
+    /// The playground canvas was drawn in a new spot, or not at all.
+    Canvas(Rect),
+    /// Time that passed while the game was closed.
+    CatchUp(Duration),
+}
+
+/// The state a recorded session started from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Start {
+    pub version: u32,
+    pub world: World,
+    pub keymap: Keymap,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Entry {
+    Start(Box<Start>),
+    Input {
+        /// Simulated time when the input arrived.
+        at: Duration,
+        input: Input,
+    },
+    End {
+        world: Box<World>,
+    },
+}
+
+/// Writes a session to a replay file as it happens.
+pub struct Recorder {
+    out: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, start: Start) -> Result<Self> {
+        let file = File::create(path).wrap_err_with(|| format!("creating {}", path.display()))?;
+        let mut recorder = Self {
+            out: BufWriter::new(file),
+        };
+        recorder.write(&Entry::Start(Box::new(start)))?;
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, at: Duration, input: Input) -> Result<()> {
+        self.write(&Entry::Input { at, input })
+    }
+
+    /// Ends the file with the world the session finished with.
+    pub fn finish(mut self, world: &World) -> Result<()> {
+        self.write(&Entry::End {
+            world: Box::new(world.clone()),
+        })?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn write(&mut self, entry: &Entry) -> Result<()> {
+        serde_json::to_writer(&mut self.out, entry)?;
+        writeln!(self.out)?;
+        Ok(())
+    }
+}
+
+/// A replay file read back in full.
+pub struct Replay {
+    pub start: Start,
+    /// Inputs with the simulated time each arrived at.
+    pub inputs: Vec<(Duration, Input)>,
+    /// The world the recorded session ended with.
+    pub end: World,
+}
+
+pub fn load(path: &Path) -> Result<Replay> {
+    let file = File::open(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+    let mut start = None;
+    let mut inputs = Vec::new();
+    let mut end = None;
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let entry: Entry = serde_json::from_str(&line?)
+            .wrap_err_with(|| format!("{} line {}", path.display(), number + 1))?;
+        match entry {
+            Entry::Start(entry) => start = Some(*entry),
+            Entry::Input { at, input } => inputs.push((at, input)),
+            Entry::End { world } => end = Some(*world),
+        }
+    }
+    let start = start.ok_or_else(|| eyre!("{} has no start entry", path.display()))?;
+    if start.version != REPLAY_VERSION {
+        bail!(
+            "replay {} has version {}, but this build only understands version {REPLAY_VERSION}",
+            path.display(),
+            start.version
+        );
+    }
+    // A session that crashed never got to write its end
+    let end = end.ok_or_else(|| eyre!("{} has no end entry", path.display()))?;
+    Ok(Replay { start, inputs, end })
+}
+
+/// Compares the world a replay ended with against the recorded one, listing
+/// every field that differs.
+pub fn check(expected: &World, actual: &World) -> Result<()> {
+    let expected = serde_json::to_value(expected)?;
+    let actual = serde_json::to_value(actual)?;
+    let differences = differences(&expected, &actual);
+    if differences.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "the replayed world differs from the recorded one:\n{}",
+        differences.join("\n")
+    )
+}
+
+fn differences(expected: &Value, actual: &Value) -> Vec<String> {
+    let (Value::Object(expected), Value::Object(actual)) = (expected, actual) else {
+        return Vec::new();
+    };
+    expected
+        .iter()
+        .filter(|(field, value)| actual.get(*field) != Some(value))
+        .map(|(field, value)| {
+            let got = actual.get(field).unwrap_or(&Value::Null);
+            format!("  {field}: recorded {value}, replayed {got}")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use ratatui::crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+
+    use super::*;
+    use crate::{assets, App};
+
+    #[test]
+    fn differences_name_the_fields() {
+        let mut world = World::default();
+        assert!(check(&world, &world.clone()).is_ok());
+        let recorded = world.clone();
+        world.pet.hunger = 42;
+        let error = check(&recorded, &world).unwrap_err().to_string();
+        assert!(error.contains("hunger: recorded 0, replayed 42"), "{error}");
+    }
+
+    #[test]
+    fn recorded_sessions_replay_to_the_same_world() {
+        let path = env::temp_dir().join(format!("tamatui-replay-{}.jsonl", process::id()));
+        let sprites = assets::built_in(assets::DEFAULT_SPECIES).unwrap();
+        let mut app = App::new(sprites, Keymap::default(), 7);
+        app.recorder = Some(Recorder::create(&path, app.start()).unwrap());
+
+        let key = |code| Input::Key(KeyEvent::from(code));
+        let click = |column, row| {
+            Input::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let mut inputs = vec![
+            Input::CatchUp(Duration::from_secs(90)),
+            Input::Canvas(Rect::new(24, 2, 54, 20)),
+            key(KeyCode::Char('b')),
+            key(KeyCode::Char('t')),
+            key(KeyCode::Char('f')),
+            key(KeyCode::Enter),
+        ];
+        for frame in 0..400 {
+            inputs.push(Input::Tick(Duration::from_millis(16 + frame % 7)));
+            if frame % 50 == 0 {
+                inputs.push(click(30 + (frame / 50) as u16 * 5, 15));
+            }
+        }
+        let count = inputs.len();
+        for input in inputs {
+            let _ = app.input(input).unwrap();
+        }
+        app.recorder.take().unwrap().finish(&app.world).unwrap();
+
+        let replayed = crate::replay(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(replayed.unwrap(), count);
+    }
+}
@@ -0,0 +1,203 @@
+//! Persisting the pet between sessions.
+//!
+//! The save file is a small JSON document stored under the XDG data directory
+//! (`$XDG_DATA_HOME/tamatui/save.json`, usually `~/.local/share/tamatui/save.json`).
+//! Every file carries a format version so older saves can be detected instead of
+//! being silently misread.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+
+use tamatui_core::{evolution::Form, World};
+
+/// Bump this when a field of [`SaveFile`] changes shape or meaning, so that older
+/// saves would be misread. Fields that are only added get `#[serde(default)]`
+/// instead, and older saves keep loading under the same version.
+pub const SAVE_VERSION: u32 = 1;
+
+/// Never simulate more than this much offline time on startup.
+const MAX_CATCH_UP: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Debug, Serialize)]
+struct SaveFile<'a> {
+    version: u32,
+    /// Seconds since the unix epoch at which the file was written.
+    saved_at: u64,
+    /// The pet, the owner's inventory, the world's RNG and everything else the
+    /// simulation needs to carry on exactly where it stopped.
+    #[serde(flatten)]
+    world: &'a World,
+    /// Forms reached by any pet so far, kept across generations.
+    unlocked: &'a BTreeSet<Form>,
+    /// The life journal, as pet ages and entries, oldest first.
+    journal: &'a [(Duration, String)],
+}
+
+/// Everything in a [`SaveFile`] besides the world, which is read on its own.
+#[derive(Debug, Deserialize)]
+struct Header {
+    version: u32,
+    saved_at: u64,
+    #[serde(default)]
+    unlocked: BTreeSet<Form>,
+    #[serde(default)]
+    journal: Vec<(Duration, String)>,
+    /// Missing from saves made before seeds were kept.
+    rng: Option<IgnoredAny>,
+}
+
+/// A world read back from the save file.
+pub struct Saved {
+    pub world: World,
+    /// How long the game was closed, capped to [`MAX_CATCH_UP`].
+    pub offline: Duration,
+    pub unlocked: BTreeSet<Form>,
+    pub journal: Vec<(Duration, String)>,
+    /// Saves made before seeds were kept have no RNG state of their own.
+    pub seeded: bool,
+}
+
+/// Location of the save file.
+pub fn save_path() -> Result<PathBuf> {
+    let dir = dirs::data_dir().ok_or_else(|| eyre!("could not determine the data directory"))?;
+    Ok(dir.join("tamatui").join("save.json"))
+}
+
+/// Loads the saved pet along with the time that passed while the game was closed.
+///
+/// Returns `Ok(None)` when there is no save file yet.
+pub fn load() -> Result<Option<Saved>> {
+    let path = save_path()?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err).wrap_err_with(|| format!("reading {}", path.display())),
+    };
+    let (header, world) =
+        parse(&contents).wrap_err_with(|| format!("parsing save file {}", path.display()))?;
+    if header.version != SAVE_VERSION {
+        bail!(
+            "save file {} has version {}, but this build only understands version {SAVE_VERSION}",
+            path.display(),
+            header.version
+        );
+    }
+
+    let offline = unix_now().saturating_sub(header.saved_at);
+    Ok(Some(Saved {
+        world,
+        offline: Duration::from_secs(offline).min(MAX_CATCH_UP),
+        seeded: header.rng.is_some(),
+        unlocked: header.unlocked,
+        journal: header.journal,
+    }))
+}
+
+/// Reads a save file's header and world. They share the top level of the file,
+/// so each is read from the whole of it, skipping the other's fields.
+fn parse(contents: &str) -> Result<(Header, World)> {
+    Ok((
+        serde_json::from_str(contents)?,
+        serde_json::from_str(contents)?,
+    ))
+}
+
+/// Writes the world, the unlocked forms and the journal to the save file, creating
+/// the data directory if needed.
+pub fn store(
+    world: &World,
+    unlocked: &BTreeSet<Form>,
+    journal: &[(Duration, String)],
+) -> Result<()> {
+    let path = save_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).wrap_err_with(|| format!("creating {}", dir.display()))?;
+    }
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        saved_at: unix_now(),
+        world,
+        unlocked,
+        journal,
+    };
+    let contents = serde_json::to_string_pretty(&save)?;
+    // Write to a sibling file first so a crash mid-write never corrupts the existing save.
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents).wrap_err_with(|| format!("writing {}", tmp.display()))?;
+    fs::rename(&tmp, &path).wrap_err_with(|| format!("writing {}", path.display()))?;
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tamatui_core::play::GameKind;
+
+    use super::*;
+
+    #[test]
+    fn saves_keep_the_whole_world() {
+        let mut world = World::new(tamatui_core::Pet::new(), 7);
+        world.step(Duration::from_millis(90_500));
+        let save = SaveFile {
+            version: SAVE_VERSION,
+            saved_at: 0,
+            world: &world,
+            unlocked: &BTreeSet::new(),
+            journal: &[(world.pet.age, "The egg hatched!".to_string())],
+        };
+        let (header, read) = parse(&serde_json::to_string(&save).unwrap()).unwrap();
+        assert!(header.rng.is_some());
+        assert_eq!(header.journal, save.journal);
+        assert_eq!(
+            serde_json::to_value(&read).unwrap(),
+            serde_json::to_value(&world).unwrap()
+        );
+    }
+
+    #[test]
+    fn games_left_running_dont_hold_up_the_catch_up() {
+        let mut pet = tamatui_core::Pet::new();
+        pet.age = Duration::from_secs(60 * 60);
+        let mut world = World::new(pet, 7);
+        world.start_game(GameKind::Guess).unwrap();
+        let save = SaveFile {
+            version: SAVE_VERSION,
+            saved_at: 0,
+            world: &world,
+            unlocked: &BTreeSet::new(),
+            journal: &[],
+        };
+        let (_, mut read) = parse(&serde_json::to_string(&save).unwrap()).unwrap();
+        let age = read.pet.age;
+        read.catch_up(Duration::from_secs(10 * 60));
+        assert!(read.game().is_none());
+        assert_eq!(read.pet.age, age + Duration::from_secs(10 * 60));
+    }
+
+    #[test]
+    fn older_saves_still_load() {
+        let (header, read) = parse(r#"{"version": 1, "saved_at": 0, "hunger": 40}"#).unwrap();
+        assert!(header.rng.is_none());
+        assert!(header.journal.is_empty());
+        assert_eq!(read.pet.hunger, 40);
+        assert_eq!(read.items.len(), World::default().items.len());
+    }
+}
@@ -0,0 +1,538 @@
+//! The screens reachable from the tab bar, besides the pet itself, and the help overlay.
+
+use ratatui::{
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Sparkline, Tabs, Widget},
+    Frame,
+};
+use tamatui_core::{evolution::Form, shop::Product, stage::Stage};
+
+use crate::{
+    centered,
+    dashboard::{self, Stat},
+    keymap::Action,
+    App, COMPACT_WIDTH,
+};
+
+/// A page of the interface, picked from the tab bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    Pet,
+    Stats,
+    Shop,
+    Journal,
+    Evolution,
+    Settings,
+}
+
+impl Screen {
+    pub const ALL: [Screen; 6] = [
+        Screen::Pet,
+        Screen::Stats,
+        Screen::Shop,
+        Screen::Journal,
+        Screen::Evolution,
+        Screen::Settings,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Screen::Pet => "Pet",
+            Screen::Stats => "Stats",
+            Screen::Shop => "Shop",
+            Screen::Journal => "Journal",
+            Screen::Evolution => "Evolution",
+            Screen::Settings => "Settings",
+        }
+    }
+
+    /// Short name for the tab bar in the compact layout.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Screen::Pet => "Pet",
+            Screen::Stats => "Stats",
+            Screen::Shop => "Shop",
+            Screen::Journal => "Log",
+            Screen::Evolution => "Evo",
+            Screen::Settings => "Set",
+        }
+    }
+
+    fn index(self) -> usize {
+        Screen::ALL.iter().position(|&s| s == self).unwrap_or(0)
+    }
+
+    pub fn next(self) -> Self {
+        Screen::ALL[(self.index() + 1) % Screen::ALL.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        Screen::ALL[(self.index() + Screen::ALL.len() - 1) % Screen::ALL.len()]
+    }
+}
+
+/// Something the owner can change on the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Speed,
+    DayClock,
+    Bell,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 3] = [Setting::Speed, Setting::DayClock, Setting::Bell];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Setting::Speed => "Speed",
+            Setting::DayClock => "Day clock",
+            Setting::Bell => "Bell",
+        }
+    }
+}
+
+/// Moves a list selection one step up or down, wrapping around.
+fn step_selection(selected: usize, len: usize, action: Option<Action>) -> usize {
+    match action {
+        Some(Action::MoveDown) => (selected + 1) % len,
+        Some(Action::MoveUp) => (selected + len - 1) % len,
+        _ => selected,
+    }
+}
+
+impl App {
+    /// The tab bar for a terminal `width` columns wide, with short names in the
+    /// compact layout and only the current screen if even those don't fit.
+    pub(crate) fn tabs(&self, width: u16) -> impl Widget {
+        let name = if width < COMPACT_WIDTH {
+            Screen::short_name
+        } else {
+            Screen::name
+        };
+        let titles = Screen::ALL.map(|screen| name(screen).to_string());
+        // Each title is padded by a space on either side, with a divider in between
+        let needed = titles.iter().map(|title| title.len() + 3).sum::<usize>() - 1;
+        let (titles, selected) = if needed > usize::from(width) {
+            let index = self.screen.index();
+            let only = format!("{} {}/{}", name(self.screen), index + 1, Screen::ALL.len());
+            (vec![only], 0)
+        } else {
+            (titles.to_vec(), self.screen.index())
+        };
+        Tabs::new(titles)
+            .select(selected)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .divider("|")
+    }
+
+    pub(crate) fn change(&mut self, setting: Setting) {
+        match setting {
+            Setting::Speed => self.clock.scale = self.clock.scale.next(),
+            Setting::DayClock => self.day_clock = self.day_clock.next(),
+            Setting::Bell => self.bell = !self.bell,
+        }
+        self.message = Some(match setting {
+            Setting::Speed => format!("Time runs at {}x", self.clock.scale.factor()),
+            Setting::DayClock => format!("Days follow {}", self.day_clock.name()),
+            Setting::Bell => if self.bell { "Bell on" } else { "Bell off" }.to_string(),
+        });
+    }
+
+    fn setting_value(&self, setting: Setting) -> String {
+        match setting {
+            Setting::Speed => format!("{}x", self.clock.scale.factor()),
+            Setting::DayClock => self.day_clock.name().to_string(),
+            Setting::Bell => if self.bell { "on" } else { "off" }.to_string(),
+        }
+    }
+
+    pub(crate) fn shop_key(&mut self, action: Option<Action>) {
+        match action {
+            Some(Action::Select) => {
+                let product = Product::ALL[self.shop_selected];
+                self.message = Some(match self.world.buy(product) {
+                    Ok(()) => format!("Bought a {}", product.name().to_lowercase()),
+                    Err(refusal) => refusal.to_string(),
+                });
+            }
+            _ => {
+                self.shop_selected = step_selection(self.shop_selected, Product::ALL.len(), action);
+            }
+        }
+    }
+
+    pub(crate) fn journal_key(&mut self, code: KeyCode, action: Option<Action>) {
+        let last = self.notifications.len().saturating_sub(1) as u16;
+        match (action, code) {
+            (Some(Action::MoveDown), _) => {
+                self.journal_scroll = (self.journal_scroll + 1).min(last);
+            }
+            (Some(Action::MoveUp), _) => {
+                self.journal_scroll = self.journal_scroll.saturating_sub(1);
+            }
+            (_, KeyCode::Home) => self.journal_scroll = 0,
+            _ => {}
+        }
+    }
+
+    pub(crate) fn settings_key(&mut self, code: KeyCode, action: Option<Action>) {
+        match (action, code) {
+            (Some(Action::MoveLeft | Action::MoveRight | Action::Select), _) => {
+                self.change(Setting::ALL[self.settings_selected]);
+            }
+            _ => {
+                self.settings_selected =
+                    step_selection(self.settings_selected, Setting::ALL.len(), action);
+            }
+        }
+    }
+
+    /// Every stat's full history, next to how the pet has been looked after.
+    pub(crate) fn render_stats_screen(&self, frame: &mut Frame, area: Rect) {
+        let pet = &self.world.pet;
+        let care = &pet.care;
+        let [details, charts] =
+            Layout::horizontal([Constraint::Length(34), Constraint::Min(0)]).areas(area);
+        let quality = care.quality();
+        let lines = vec![
+            Line::from(format!(
+                "Stage: {} {}",
+                pet.stage().name(),
+                pet.form.map_or("", |form| form.name())
+            )),
+            Line::from(format!(
+                "Age: {}m  Weight: {}g",
+                pet.age.as_secs() / 60,
+                pet.weight
+            )),
+            Line::from(format!("Mood: {}", pet.mood().name())),
+            Line::from(format!(
+                "Illness: {}",
+                pet.illness.map_or("None", |illness| illness.name())
+            )),
+            // Traits stay hidden until the pet dies
+            Line::from("Personality: ???"),
+            Line::default(),
+            Line::styled("This stage", Style::default().add_modifier(Modifier::BOLD)),
+            Line::from(vec![
+                Span::raw("  Care: "),
+                Span::styled(
+                    quality.name(),
+                    Style::default().fg(dashboard::quality_color(quality)),
+                ),
+            ]),
+            Line::from(format!(
+                "  Time in danger: {:.0}%",
+                care.danger_share() * 100.0
+            )),
+            Line::from(format!("  Unanswered calls: {}", care.mistakes)),
+            Line::from(format!(
+                "  Meals / treats: {} / {}",
+                care.meals, care.treats
+            )),
+            Line::from(format!("  Overfed: {} times", care.overfeeds)),
+            Line::default(),
+            Line::styled("Lifetime", Style::default().add_modifier(Modifier::BOLD)),
+            Line::from(format!("  Care mistakes: {}", pet.care_mistakes)),
+            Line::from(format!("  Waste lying around: {}", pet.waste.len())),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Details")),
+            details,
+        );
+
+        let rows =
+            Layout::vertical(Stat::ALL.map(|_| Constraint::Ratio(1, Stat::ALL.len() as u32)))
+                .split(charts);
+        for (stat, row) in Stat::ALL.into_iter().zip(rows.iter()) {
+            let value = stat.value(pet);
+            let trend = self.history.trend(stat, value);
+            let color = stat.color(value);
+            let title = Line::from(vec![
+                Span::styled(
+                    format!("{} {value} ", stat.name()),
+                    Style::default().fg(color),
+                ),
+                Span::styled(trend.arrow(), Style::default().fg(trend.color(stat))),
+            ]);
+            let block = Block::bordered().title(title);
+            let width = block.inner(*row).width as usize;
+            let series = self.history.series(stat);
+            let shown = &series[series.len().saturating_sub(width)..];
+            frame.render_widget(
+                Sparkline::default()
+                    .block(block)
+                    .data(shown)
+                    .max(100)
+                    .style(Style::default().fg(color)),
+                *row,
+            );
+        }
+    }
+
+    pub(crate) fn render_shop(&self, frame: &mut Frame, area: Rect) {
+        let inventory = &self.world.inventory;
+        let items: Vec<ListItem> = Product::ALL
+            .iter()
+            .map(|&product| {
+                ListItem::new(format!(
+                    "{:<9}{:>3} coins  have {:<3} {}",
+                    product.name(),
+                    product.price(),
+                    inventory.count(product),
+                    product.describe()
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(
+                "Shop: {} coins ({} to buy, win games to earn more)",
+                inventory.coins,
+                self.keymap.hint(Action::Select)
+            )))
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Yellow));
+        frame.render_stateful_widget(
+            list,
+            area,
+            &mut ListState::default().with_selected(Some(self.shop_selected)),
+        );
+    }
+
+    /// Everything that happened to the pet, newest first.
+    pub(crate) fn journal(&self) -> impl Widget {
+        let lines: Vec<Line> = self
+            .notifications
+            .iter()
+            .rev()
+            .map(|(age, text)| {
+                let minutes = age.as_secs() / 60;
+                Line::from(format!("{}h{:02} {text}", minutes / 60, minutes % 60))
+            })
+            .collect();
+        Paragraph::new(lines)
+            .block(
+                Block::bordered().title(format!("Journal ({} entries)", self.notifications.len())),
+            )
+            .scroll((self.journal_scroll, 0))
+    }
+
+    /// Every form, grouped by stage, with the ones never reached kept hidden.
+    pub(crate) fn evolution_tree(&self) -> impl Widget {
+        let current = self.world.pet.form;
+        let name = |form: Form| {
+            if self.unlocked.contains(&form) {
+                form.name()
+            } else {
+                "???"
+            }
+        };
+        let mut lines = Vec::new();
+        for stage in [Stage::Baby, Stage::Child, Stage::Teen, Stage::Adult] {
+            lines.push(Line::styled(
+                stage.name(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            for form in Form::ALL.into_iter().filter(|form| form.stage() == stage) {
+                let style = if current == Some(form) {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else if self.unlocked.contains(&form) {
+                    Style::default().fg(Color::White)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                let mut spans = vec![Span::styled(format!("  {}", name(form)), style)];
+                if !self.unlocked.contains(&form) {
+                    spans.push(Span::styled(
+                        format!("  ({})", form.hint()),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                } else if !form.branches().is_empty() {
+                    let branches: Vec<_> = form.branches().iter().map(|&b| name(b)).collect();
+                    spans.push(Span::raw(format!(" -> {}", branches.join(", "))));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        lines.push(Line::default());
+        lines.push(Line::from(format!(
+            "{}/{} forms unlocked",
+            self.unlocked.len(),
+            Form::ALL.len()
+        )));
+        Paragraph::new(lines).block(Block::bordered().title("Evolution tree"))
+    }
+
+    /// The settings that can be changed here, above every active key binding.
+    pub(crate) fn render_settings(&self, frame: &mut Frame, area: Rect) {
+        let [settings, keys] = Layout::vertical([
+            Constraint::Length(Setting::ALL.len() as u16 + 2),
+            Constraint::Min(0),
+        ])
+        .areas(area);
+        let items: Vec<ListItem> = Setting::ALL
+            .iter()
+            .map(|&setting| {
+                ListItem::new(format!(
+                    "{:<12}{}",
+                    setting.name(),
+                    self.setting_value(setting)
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        "Settings ({} to change)",
+                        self.keymap.hint(Action::Select)
+                    ))
+                    .title_bottom(format!("Seed {}", self.world.seed)),
+            )
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Yellow));
+        frame.render_stateful_widget(
+            list,
+            settings,
+            &mut ListState::default().with_selected(Some(self.settings_selected)),
+        );
+
+        let lines: Vec<Line> = Action::ALL
+            .iter()
+            .map(|&action| {
+                Line::from(vec![
+                    Span::raw(format!("{:<16}", action.name())),
+                    Span::styled(
+                        format!("{:<20}", self.keymap.describe(action)),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(action.describe(), Style::default().fg(Color::Gray)),
+                ])
+            })
+            .collect();
+        let title = match &self.keymap_path {
+            Some(path) => format!("Keymap (edit {})", path.display()),
+            None => "Keymap".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(title)),
+            keys,
+        );
+    }
+
+    /// Keys that do something right now, most specific first.
+    fn help_entries(&self) -> Vec<(String, &'static str)> {
+        let keys = |action| self.keymap.describe(action);
+        let fixed = |key: &str, action| (key.to_string(), action);
+        let mut entries = if self.world.game().is_some() {
+            vec![
+                (keys(Action::MoveLeft), "Left"),
+                (keys(Action::MoveRight), "Right"),
+                (keys(Action::Back), "Stop playing"),
+            ]
+        } else if self.menu.is_some() {
+            vec![
+                (keys(Action::MoveUp), "Move up"),
+                (keys(Action::MoveDown), "Move down"),
+                (keys(Action::Select), "Choose"),
+                fixed("1-9", "Choose by number"),
+                (keys(Action::Back), "Close the menu"),
+            ]
+        } else {
+            match self.screen {
+                Screen::Pet if self.world.pet.is_dead() => {
+                    vec![(keys(Action::NewEgg), "Hatch a new egg")]
+                }
+                Screen::Pet => [
+                    Action::Feed,
+                    Action::Play,
+                    Action::Clean,
+                    Action::Medicine,
+                    Action::Scold,
+                    Action::Praise,
+                    Action::Sleep,
+                    Action::MoveUp,
+                    Action::MoveDown,
+                    Action::MoveLeft,
+                    Action::MoveRight,
+                    Action::Speed,
+                    Action::DayClock,
+                    Action::Bell,
+                ]
+                .map(|action| (keys(action), action.describe()))
+                .into_iter()
+                .chain([
+                    fixed("Click pet", "Pet it"),
+                    fixed("Click ground", "Drop a meal there"),
+                    fixed("Drag toy", "Move the toy"),
+                ])
+                .collect(),
+                Screen::Shop => vec![
+                    (keys(Action::MoveUp), "Previous product"),
+                    (keys(Action::MoveDown), "Next product"),
+                    (keys(Action::Select), "Buy it"),
+                ],
+                Screen::Journal => vec![
+                    (keys(Action::MoveUp), "Scroll up"),
+                    (keys(Action::MoveDown), "Scroll down"),
+                    fixed("Home", "Back to the newest"),
+                ],
+                Screen::Settings => vec![
+                    (keys(Action::MoveUp), "Previous setting"),
+                    (keys(Action::MoveDown), "Next setting"),
+                    (keys(Action::Select), "Change it"),
+                ],
+                Screen::Stats | Screen::Evolution => Vec::new(),
+            }
+        };
+        if self.world.game().is_none() && self.menu.is_none() {
+            entries.extend([
+                (keys(Action::Pause), Action::Pause.describe()),
+                (keys(Action::Step), Action::Step.describe()),
+                (keys(Action::NextScreen), Action::NextScreen.describe()),
+                (
+                    keys(Action::PreviousScreen),
+                    Action::PreviousScreen.describe(),
+                ),
+                fixed("1-6", "Jump to a screen"),
+                (keys(Action::Suspend), Action::Suspend.describe()),
+                (keys(Action::Quit), Action::Quit.describe()),
+            ]);
+        }
+        entries.push(fixed("any key", "Close this help"));
+        entries
+    }
+
+    pub(crate) fn render_help(&self, frame: &mut Frame, area: Rect) {
+        let context = if self.world.game().is_some() {
+            "Game"
+        } else if self.menu.is_some() {
+            "Menu"
+        } else {
+            self.screen.name()
+        };
+        let entries = self.help_entries();
+        let lines: Vec<Line> = entries
+            .iter()
+            .map(|(key, action)| {
+                Line::from(vec![
+                    Span::styled(format!("{key:>14}  "), Style::default().fg(Color::Yellow)),
+                    Span::raw(*action),
+                ])
+            })
+            .collect();
+        let popup = centered(area, 48, entries.len() as u16 + 2);
+        frame.render_widget(Clear, popup);
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(format!("Help: {context}"))),
+            popup,
+        );
+    }
+}
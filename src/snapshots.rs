@@ -0,0 +1,182 @@
+//! Snapshots of whole frames rendered without a terminal.
+//!
+//! Each test draws the [`App`] into a [`TestBackend`] and compares the text on
+//! screen against a file in `src/snapshots`. Colors aren't compared, only the
+//! characters. After an intended change to the layout, rewrite the files with
+//!
+//! ```sh
+//! INSTA_UPDATE=always cargo test
+//! ```
+//!
+//! or go through the changes one at a time with `cargo insta review`.
+
+use std::{path::PathBuf, time::Duration};
+
+use insta::assert_snapshot;
+use ratatui::{backend::TestBackend, symbols::Marker, Terminal};
+use tamatui_core::{
+    care::{Call, Need},
+    evolution::Form,
+    food::Food,
+    health::Illness,
+    personality::Misbehavior,
+    play::GameKind,
+    stage::DeathCause,
+    Pet,
+};
+
+use crate::{assets, keymap::Keymap, replay::Input, screens::Screen, App, Menu};
+
+/// Terminal sizes every pet state is drawn at: roomy, the usual default, and
+/// small enough for the compact layout.
+const SIZES: [(u16, u16); 3] = [(120, 40), (80, 24), (50, 16)];
+
+/// Every way the playground can be drawn, in the order the game cycles through them.
+const MARKERS: [(Marker, &str); 5] = [
+    (Marker::Braille, "braille"),
+    (Marker::Block, "block"),
+    (Marker::HalfBlock, "half_block"),
+    (Marker::Bar, "bar"),
+    (Marker::Dot, "dot"),
+];
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// A fresh app with the built-in sprites, so sprite overrides on the machine
+/// running the tests can't change the pictures.
+fn app(pet: Pet) -> App {
+    let sprites = assets::built_in(assets::DEFAULT_SPECIES).unwrap();
+    let mut app = App::new(sprites, Keymap::default(), 0);
+    app.world.pet = pet;
+    app
+}
+
+/// A well looked after adult in the middle of the playground.
+fn adult() -> Pet {
+    let mut pet = Pet::new();
+    pet.age = 5 * 60 * MINUTE;
+    pet.form = Some(Form::Star);
+    pet.weight = 20;
+    pet
+}
+
+/// An app whose history shows `pet` getting hungrier, sadder and more tired over
+/// the last ten minutes, ending up as it is.
+fn with_history(pet: Pet) -> App {
+    let mut app = app(pet.clone());
+    let mut past = pet;
+    let now = past.age;
+    for sample in 0..60 {
+        past.age = now - (59 - sample) * Duration::from_secs(10);
+        past.hunger = sample * 3 / 2;
+        past.happiness = 100 - sample;
+        past.energy = 100 - sample % 30 * 2;
+        app.history.record(&past);
+    }
+    app.world.pet = past;
+    app
+}
+
+/// Draws `app` the way the game does, fitting the playground to the canvas
+/// after the first frame, and returns the screen.
+fn render(app: &mut App, width: u16, height: u16) -> TestBackend {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+    let _ = app.apply(Input::Canvas(app.canvas.get()));
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+    terminal.backend().clone()
+}
+
+/// The pet states worth looking at, each set up from a fresh app.
+fn states() -> Vec<(String, App)> {
+    let mut states = vec![("egg".to_string(), app(Pet::new()))];
+
+    let mut baby = Pet::new();
+    baby.age = 2 * MINUTE;
+    states.push(("baby".to_string(), app(baby)));
+
+    states.push(("adult".to_string(), app(adult())));
+
+    let mut asleep = adult();
+    asleep.asleep = true;
+    asleep.energy = 20;
+    states.push(("asleep".to_string(), app(asleep)));
+
+    states.push(("declining".to_string(), with_history(adult())));
+
+    let mut sick = adult();
+    sick.illness = Some(Illness::Cold);
+    sick.health = 40;
+    states.push(("sick".to_string(), app(sick)));
+
+    let mut calling = adult();
+    calling.hunger = 90;
+    calling.call = Some(Call {
+        need: Need::Food,
+        since: calling.age,
+        ignored: false,
+    });
+    states.push(("calling".to_string(), app(calling)));
+
+    let mut misbehaving = adult();
+    misbehaving.misbehaving = Some(Misbehavior::FakeCall);
+    states.push(("misbehaving".to_string(), app(misbehaving)));
+
+    let mut messy = adult();
+    messy.cleanliness = 30;
+    messy.waste = vec![(40.0, 20.0), (150.0, 30.0)];
+    let mut messy = app(messy);
+    messy.world.drop_food(Food::Meal, (60.0, 70.0)).unwrap();
+    states.push(("messy".to_string(), messy));
+
+    let mut menu = app(adult());
+    menu.menu = Some((Menu::Feed, 1));
+    states.push(("feed_menu".to_string(), menu));
+
+    for kind in GameKind::ALL {
+        let mut playing = app(adult());
+        playing.world.start_game(kind).unwrap();
+        // Game names are full sentences, so the file goes by the variant
+        states.push((format!("{kind:?}").to_lowercase(), playing));
+    }
+
+    let mut dead = adult();
+    dead.cause_of_death = Some(DeathCause::OldAge);
+    states.push(("dead".to_string(), app(dead)));
+
+    states
+}
+
+#[test]
+fn pet_states() {
+    for (width, height) in SIZES {
+        for (name, mut app) in states() {
+            let screen = render(&mut app, width, height);
+            assert_snapshot!(format!("{name}_{width}x{height}"), screen);
+        }
+    }
+}
+
+#[test]
+fn markers() {
+    for (marker, name) in MARKERS {
+        let mut app = app(adult());
+        app.marker = marker;
+        let screen = render(&mut app, 80, 24);
+        assert_snapshot!(format!("marker_{name}"), screen);
+    }
+}
+
+#[test]
+fn screens() {
+    // The pet screen is already covered by the pet states
+    for shown in &Screen::ALL[1..] {
+        let mut app = with_history(adult());
+        app.screen = *shown;
+        // The real path depends on the machine running the tests
+        app.keymap_path = Some(PathBuf::from("~/.config/tamatui/keymap.toml"));
+        let screen = render(&mut app, 80, 24);
+        let name = shown.name().to_lowercase();
+        assert_snapshot!(format!("screen_{name}"), screen);
+    }
+}
@@ -0,0 +1,303 @@
+//! The pet's sprite, drawn from the grids in its [`SpriteSet`].
+//!
+//! A sprite is a list of [`Piece`]s: the current frame of the stage's grid, then eyes
+//! and a mouth drawn over its face. Which eyes and mouth are used depends on the
+//! pet's [`Mood`], and an [`Animation`] frame adds blinking and a gentle bob.
+
+use std::{f64::consts::TAU, time::Duration};
+
+use ratatui::{
+    style::Color,
+    widgets::canvas::{Circle, Line, Painter, Shape},
+};
+use tamatui_core::{health::Illness, mood::Mood, stage::Stage, Pet};
+
+use crate::assets::{Frame, SpriteSet};
+
+/// How often the pet blinks, and how long its eyes stay closed.
+const BLINK_EVERY: Duration = Duration::from_millis(3000);
+const BLINK_FOR: Duration = Duration::from_millis(150);
+/// Time for one full bob up and down, and how far the pet moves.
+const BOB_PERIOD: Duration = Duration::from_millis(1200);
+const BOB_HEIGHT: f64 = 1.0;
+/// Sick pets turn this color.
+const SICK_TINT: Color = Color::LightGreen;
+
+/// One frame of the idle animation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Animation {
+    /// Time since the animation started, used to pick the sprite frame.
+    pub time: Duration,
+    pub blinking: bool,
+    /// Vertical offset added to the whole sprite.
+    pub bob: f64,
+}
+
+impl Animation {
+    /// The animation frame at `time` since the animation started.
+    pub fn at(time: Duration) -> Self {
+        let blink_phase = time.as_millis() % BLINK_EVERY.as_millis();
+        let bob_phase =
+            (time.as_millis() % BOB_PERIOD.as_millis()) as f64 / BOB_PERIOD.as_millis() as f64;
+        Self {
+            time,
+            blinking: blink_phase >= (BLINK_EVERY - BLINK_FOR).as_millis(),
+            bob: (bob_phase * TAU).sin() * BOB_HEIGHT,
+        }
+    }
+}
+
+/// A single shape making up part of a sprite.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Piece {
+    Circle(Circle),
+    Line(Line),
+    Pixels(Pixels),
+}
+
+impl Shape for Piece {
+    fn draw(&self, painter: &mut Painter) {
+        match self {
+            Piece::Circle(circle) => circle.draw(painter),
+            Piece::Line(line) => line.draw(painter),
+            Piece::Pixels(pixels) => pixels.draw(painter),
+        }
+    }
+}
+
+/// A frame of a sprite grid placed in the playground.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pixels {
+    /// Top left corner of the grid.
+    pub left: f64,
+    pub top: f64,
+    /// Size of one cell.
+    pub cell: f64,
+    pub frame: Frame,
+    /// Replaces every color in the frame when set.
+    pub tint: Option<Color>,
+}
+
+impl Shape for Pixels {
+    fn draw(&self, painter: &mut Painter) {
+        // Inset the corners a little so neighbouring cells don't overlap
+        let inset = self.cell * 0.01;
+        for row in 0..self.frame.height {
+            for col in 0..self.frame.width {
+                let Some(color) = self.frame.pixel(col, row) else {
+                    continue;
+                };
+                let x = self.left + col as f64 * self.cell;
+                let y = self.top - row as f64 * self.cell;
+                let corners = (
+                    painter.get_point(x + inset, y - inset),
+                    painter.get_point(x + self.cell - inset, y - self.cell + inset),
+                );
+                let (Some((x1, y1)), Some((x2, y2))) = corners else {
+                    continue;
+                };
+                for px in x1.min(x2)..=x1.max(x2) {
+                    for py in y1.min(y2)..=y1.max(y2) {
+                        painter.paint(px, py, self.tint.unwrap_or(color));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether `point` lands on the pet's sprite, give or take the empty cells around
+/// its edges.
+pub fn touches(pet: &Pet, sprites: &SpriteSet, point: (f64, f64)) -> bool {
+    let Some(sprite) = sprites.get(pet.stage()) else {
+        return false;
+    };
+    let frame = sprite.frame_at(Duration::ZERO);
+    let (x, y) = pet.position;
+    (point.0 - x).abs() <= frame.width as f64 * sprite.cell / 2.0
+        && (point.1 - y).abs() <= frame.height as f64 * sprite.cell / 2.0
+}
+
+/// The pet drawn at its position in the playground.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PetSprite {
+    pieces: Vec<Piece>,
+}
+
+impl PetSprite {
+    /// Builds the sprite for the pet's current stage from `sprites`. Stages without
+    /// a sprite (only the dead) draw nothing.
+    pub fn new(pet: &Pet, animation: Animation, sprites: &SpriteSet) -> Self {
+        let stage = pet.stage();
+        let Some(sprite) = sprites.get(stage) else {
+            return Self { pieces: Vec::new() };
+        };
+        let mood = pet.mood();
+        let (x, y) = pet.position;
+        // Eggs and sleeping pets sit still
+        let still = stage == Stage::Egg || pet.asleep;
+        let bob = if still { 0.0 } else { animation.bob };
+        let frame = sprite.frame_at(if still {
+            Duration::ZERO
+        } else {
+            animation.time
+        });
+        let left = x - frame.width as f64 * sprite.cell / 2.0;
+        let top = y + bob + frame.height as f64 * sprite.cell / 2.0;
+        let mut pieces = vec![Piece::Pixels(Pixels {
+            left,
+            top,
+            cell: sprite.cell,
+            frame: frame.clone(),
+            tint: (mood == Mood::Sick).then_some(SICK_TINT),
+        })];
+        if let Some(face) = sprite.face {
+            let mut builder = Builder {
+                x: left + (face.col + 0.5) * sprite.cell,
+                y: top - (face.row + 0.5) * sprite.cell,
+                radius: face.radius * sprite.cell,
+                pieces,
+            };
+            builder.eyes(mood, animation.blinking || pet.asleep);
+            builder.mouth(mood);
+            if let Some(illness) = pet.illness {
+                builder.symptoms(illness);
+            }
+            pieces = builder.pieces;
+        }
+        Self { pieces }
+    }
+}
+
+impl Shape for PetSprite {
+    fn draw(&self, painter: &mut Painter) {
+        for piece in &self.pieces {
+            piece.draw(painter);
+        }
+    }
+}
+
+/// Collects pieces relative to the center and size of the face.
+struct Builder {
+    x: f64,
+    y: f64,
+    radius: f64,
+    pieces: Vec<Piece>,
+}
+
+impl Builder {
+    /// Adds a line between two points given as fractions of the face radius.
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color) {
+        self.pieces.push(Piece::Line(Line::new(
+            self.x + from.0 * self.radius,
+            self.y + from.1 * self.radius,
+            self.x + to.0 * self.radius,
+            self.y + to.1 * self.radius,
+            color,
+        )));
+    }
+
+    /// Adds a line through each of `points` in turn.
+    fn polyline(&mut self, points: &[(f64, f64)], color: Color) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], color);
+        }
+    }
+
+    fn circle(&mut self, center: (f64, f64), radius: f64, color: Color) {
+        self.pieces.push(Piece::Circle(Circle {
+            x: self.x + center.0 * self.radius,
+            y: self.y + center.1 * self.radius,
+            radius: radius * self.radius,
+            color,
+        }));
+    }
+
+    fn eyes(&mut self, mood: Mood, blinking: bool) {
+        for side in [-1.0, 1.0] {
+            let (x, y) = (side * 0.4, 0.3);
+            if blinking {
+                self.line((x - 0.15, y), (x + 0.15, y), Color::White);
+                continue;
+            }
+            match mood {
+                // Upturned arcs
+                Mood::Happy => {
+                    self.polyline(&[(x - 0.15, y), (x, y + 0.15), (x + 0.15, y)], Color::White);
+                }
+                Mood::Sick => {
+                    self.line((x - 0.12, y - 0.12), (x + 0.12, y + 0.12), Color::White);
+                    self.line((x - 0.12, y + 0.12), (x + 0.12, y - 0.12), Color::White);
+                }
+                // Droopy brows above round eyes
+                Mood::Sad => {
+                    self.circle((x, y), 0.08, Color::White);
+                    self.line(
+                        (x - side * 0.15, y + 0.25),
+                        (x + side * 0.15, y + 0.15),
+                        Color::White,
+                    );
+                }
+                Mood::Content | Mood::Hungry => self.circle((x, y), 0.08, Color::White),
+            }
+        }
+    }
+
+    fn mouth(&mut self, mood: Mood) {
+        let color = Color::Red;
+        match mood {
+            Mood::Happy => self.polyline(
+                &[(-0.45, -0.25), (-0.2, -0.45), (0.2, -0.45), (0.45, -0.25)],
+                color,
+            ),
+            Mood::Content => self.polyline(&[(-0.3, -0.35), (0.0, -0.42), (0.3, -0.35)], color),
+            Mood::Sad => self.polyline(
+                &[(-0.4, -0.55), (-0.2, -0.4), (0.2, -0.4), (0.4, -0.55)],
+                color,
+            ),
+            // Open wide, waiting for food
+            Mood::Hungry => self.circle((0.0, -0.4), 0.15, color),
+            Mood::Sick => self.polyline(
+                &[
+                    (-0.4, -0.4),
+                    (-0.2, -0.3),
+                    (0.0, -0.4),
+                    (0.2, -0.3),
+                    (0.4, -0.4),
+                ],
+                color,
+            ),
+        }
+    }
+
+    fn symptoms(&mut self, illness: Illness) {
+        match illness {
+            // A runny nose
+            Illness::Cold => {
+                self.line((0.0, -0.05), (0.05, -0.3), Color::LightBlue);
+                self.circle((0.05, -0.35), 0.06, Color::LightBlue);
+            }
+            // Swirls on the belly
+            Illness::Tummyache => {
+                for y in [-0.75, -0.9] {
+                    self.polyline(
+                        &[
+                            (-0.3, y),
+                            (-0.15, y + 0.08),
+                            (0.0, y),
+                            (0.15, y + 0.08),
+                            (0.3, y),
+                        ],
+                        Color::Green,
+                    );
+                }
+            }
+            // Flushed cheeks and a bead of sweat
+            Illness::Fever => {
+                self.circle((-0.65, -0.05), 0.12, Color::Red);
+                self.circle((0.65, -0.05), 0.12, Color::Red);
+                self.line((0.85, 0.75), (0.95, 0.55), Color::LightCyan);
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Taking over the terminal and reliably handing it back.
+//!
+//! The terminal is put back in its normal state however the game ends: a normal
+//! quit, a panic, or being told to stop with SIGINT or SIGTERM. SIGTSTP suspends
+//! the game like any other program, with the terminal restored while it's stopped
+//! and taken over again on SIGCONT.
+
+use std::{io, panic};
+
+use color_eyre::Result;
+use ratatui::{
+    crossterm::{
+        event::{DisableMouseCapture, EnableMouseCapture},
+        execute,
+        terminal::{enable_raw_mode, EnterAlternateScreen},
+    },
+    DefaultTerminal,
+};
+use signal_hook::{
+    consts::{SIGCONT, SIGINT, SIGTERM, SIGTSTP},
+    low_level,
+};
+
+/// What a signal asks the game to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    /// Save and exit.
+    Quit,
+    /// Stop until resumed.
+    Suspend,
+    /// Carry on after being stopped.
+    Resume,
+}
+
+/// Signals caught since the game started, collected without blocking.
+pub struct Signals(signal_hook::iterator::Signals);
+
+impl Signals {
+    pub fn register() -> Result<Self> {
+        Ok(Self(signal_hook::iterator::Signals::new([
+            SIGINT, SIGTERM, SIGTSTP, SIGCONT,
+        ])?))
+    }
+
+    /// Signals that arrived since the last call.
+    pub fn pending(&mut self) -> Vec<Interrupt> {
+        self.0
+            .pending()
+            .filter_map(|signal| match signal {
+                SIGINT | SIGTERM => Some(Interrupt::Quit),
+                SIGTSTP => Some(Interrupt::Suspend),
+                SIGCONT => Some(Interrupt::Resume),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Switches to the alternate screen in raw mode with the mouse captured.
+///
+/// Installs a panic hook first, so a panic restores the terminal before its
+/// message is printed.
+pub fn init() -> Result<DefaultTerminal> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        hook(info);
+    }));
+    let terminal = ratatui::try_init()?;
+    execute!(io::stdout(), EnableMouseCapture)?;
+    Ok(terminal)
+}
+
+/// Puts the terminal back the way it was. Safe to call more than once.
+pub fn restore() {
+    // Best effort: there's nothing left to do if the terminal won't cooperate
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    let _ = ratatui::try_restore();
+}
+
+/// Hands the terminal back and stops the process until it's continued.
+pub fn suspend(terminal: &mut DefaultTerminal) -> Result<()> {
+    restore();
+    // Does what SIGTSTP would have done had it not been caught
+    low_level::emulate_default_handler(SIGTSTP)?;
+    resume(terminal)
+}
+
+/// Takes the terminal over again after being stopped and redraws from scratch,
+/// since the shell may have drawn over the screen in the meantime.
+pub fn resume(terminal: &mut DefaultTerminal) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    Ok(())
+}
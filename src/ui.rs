@@ -0,0 +1,292 @@
+//! Drawing the simulation onto ratatui canvases.
+
+use std::time::Duration;
+
+use chrono::Timelike;
+use ratatui::{
+    layout::{Position, Rect},
+    style::Color,
+    widgets::canvas::{Circle, Context, Line, Rectangle},
+};
+use tamatui_core::{
+    behavior::{Item, ItemKind},
+    play::{CatchGame, Game, GuessGame, Side},
+    world::{Bounds, PLAYGROUND},
+};
+
+use crate::keymap::{Action, Keymap};
+
+/// Terminal cells are about twice as tall as they are wide.
+const CELL_ASPECT: f64 = 2.0;
+
+/// Hour of the day a pet hatches at on the simulated clock.
+const HATCH_HOUR: u64 = 8;
+
+/// Where the time of day for the day-night cycle comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DayClock {
+    /// The pet's own clock, which runs faster when time is sped up.
+    #[default]
+    Simulated,
+    /// The time on the computer running the game.
+    Local,
+}
+
+impl DayClock {
+    pub fn next(self) -> Self {
+        match self {
+            DayClock::Simulated => DayClock::Local,
+            DayClock::Local => DayClock::Simulated,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DayClock::Simulated => "pet time",
+            DayClock::Local => "local time",
+        }
+    }
+
+    /// The current hour, given how old the pet is.
+    pub fn hour(self, age: Duration) -> u32 {
+        match self {
+            DayClock::Simulated => ((age.as_secs() / 3600 + HATCH_HOUR) % 24) as u32,
+            DayClock::Local => chrono::Local::now().hour(),
+        }
+    }
+}
+
+/// How light it is outside, which sets the playground's colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Daylight {
+    Day,
+    Twilight,
+    Night,
+}
+
+impl Daylight {
+    pub fn at(hour: u32) -> Self {
+        match hour {
+            7..=18 => Daylight::Day,
+            6 | 19 | 20 => Daylight::Twilight,
+            _ => Daylight::Night,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Daylight::Day => "Day",
+            Daylight::Twilight => "Twilight",
+            Daylight::Night => "Night",
+        }
+    }
+
+    /// Background of the playground canvas.
+    pub fn sky(self) -> Color {
+        match self {
+            Daylight::Day => Color::Reset,
+            Daylight::Twilight => Color::Rgb(60, 40, 80),
+            Daylight::Night => Color::Rgb(10, 10, 35),
+        }
+    }
+
+    /// Color of the playground's border and title.
+    pub fn frame(self) -> Color {
+        match self {
+            Daylight::Day => Color::White,
+            Daylight::Twilight => Color::LightMagenta,
+            Daylight::Night => Color::Blue,
+        }
+    }
+}
+
+/// The playground shaped to fill a canvas whose inner area is `area`, so the
+/// pet doesn't look squashed or stretched.
+pub fn playground_for(area: Rect) -> Bounds {
+    let height = f64::from(area.height.max(1)) * CELL_ASPECT;
+    Bounds::fitting(f64::from(area.width) / height)
+}
+
+/// Maps a terminal cell inside `area`, the inner area of a canvas showing `bounds`,
+/// to the playground point at the middle of that cell.
+pub fn to_playground(area: Rect, bounds: Bounds, column: u16, row: u16) -> Option<(f64, f64)> {
+    if !area.contains(Position::new(column, row)) {
+        return None;
+    }
+    // Canvas rows count down from the top, while the playground's y axis points up
+    let x =
+        bounds.left + (f64::from(column - area.x) + 0.5) / f64::from(area.width) * bounds.width();
+    let y = bounds.top - (f64::from(row - area.y) + 0.5) / f64::from(area.height) * bounds.height();
+    Some((x, y))
+}
+
+/// Draws the bowl and toys lying around the playground.
+pub fn paint_items(ctx: &mut Context, items: &[Item]) {
+    for item in items {
+        let (x, y) = item.position;
+        match item.kind {
+            ItemKind::Bowl => ctx.draw(&Rectangle {
+                x: x - 5.0,
+                y: y - 2.0,
+                width: 10.0,
+                height: 4.0,
+                color: Color::LightBlue,
+            }),
+            ItemKind::Toy => ctx.draw(&Circle {
+                x,
+                y,
+                radius: 3.0,
+                color: Color::LightRed,
+            }),
+            ItemKind::Food(_) => ctx.draw(&Circle {
+                x,
+                y,
+                radius: 1.5,
+                color: Color::Yellow,
+            }),
+        }
+    }
+}
+
+/// Draws the piles of waste the pet has left behind.
+pub fn paint_waste(ctx: &mut Context, piles: &[(f64, f64)]) {
+    for &(x, y) in piles {
+        for (dy, radius) in [(0.0, 3.0), (2.5, 2.0), (4.5, 1.0)] {
+            ctx.draw(&Circle {
+                x,
+                y: y + dy,
+                radius,
+                color: Color::Rgb(140, 90, 40),
+            });
+        }
+    }
+}
+
+/// A broom sweeping across the playground from left to right, `progress` of the
+/// way there. Piles it hasn't reached yet are still drawn.
+pub fn paint_sweep(ctx: &mut Context, playground: Bounds, progress: f64, piles: &[(f64, f64)]) {
+    let x = playground.left + playground.width() * progress;
+    let ahead: Vec<(f64, f64)> = piles.iter().copied().filter(|pile| pile.0 > x).collect();
+    paint_waste(ctx, &ahead);
+    ctx.draw(&Line::new(
+        x,
+        playground.bottom,
+        x,
+        playground.top,
+        Color::LightCyan,
+    ));
+    // Bristles
+    for dx in [-4.0, -2.0, 0.0, 2.0, 4.0] {
+        ctx.draw(&Line::new(
+            x,
+            playground.bottom + 8.0,
+            x + dx,
+            playground.bottom,
+            Color::Yellow,
+        ));
+    }
+}
+
+pub fn paint_game(ctx: &mut Context, game: &Game, keymap: &Keymap) {
+    match game {
+        Game::Guess(game) => paint_guess(ctx, game, keymap),
+        Game::Catch(game) => paint_catch(ctx, game),
+    }
+}
+
+fn paint_guess(ctx: &mut Context, game: &GuessGame, keymap: &Keymap) {
+    let (center, middle) = PLAYGROUND.center();
+    ctx.draw(&Circle {
+        x: center,
+        y: middle,
+        radius: 10.0,
+        color: Color::Yellow,
+    });
+    // The pet's eyes point to where it looked last round
+    let look = match game.last() {
+        Some((Side::Left, _)) => -4.0,
+        Some((Side::Right, _)) => 4.0,
+        None => 0.0,
+    };
+    for eye in [-3.0, 3.0] {
+        ctx.draw(&Circle {
+            x: center + eye + look,
+            y: middle + 3.0,
+            radius: 1.0,
+            color: Color::White,
+        });
+    }
+    ctx.print(
+        PLAYGROUND.left + 5.0,
+        middle,
+        format!("<- {}", keymap.hint(Action::MoveLeft)),
+    );
+    ctx.print(
+        PLAYGROUND.right - 25.0,
+        middle,
+        format!("{} ->", keymap.hint(Action::MoveRight)),
+    );
+    let verdict = match game.last() {
+        Some((_, true)) => "Yes!",
+        Some((_, false)) => "Nope",
+        None => "Which way will I look?",
+    };
+    ctx.print(center - 20.0, PLAYGROUND.top - 10.0, verdict);
+    ctx.print(
+        center - 20.0,
+        PLAYGROUND.bottom + 5.0,
+        format!(
+            "Round {}/{}  Correct {}",
+            (game.round() + 1).min(GuessGame::ROUNDS),
+            GuessGame::ROUNDS,
+            game.correct()
+        ),
+    );
+}
+
+fn paint_catch(ctx: &mut Context, game: &CatchGame) {
+    for &(x, y) in game.treats() {
+        ctx.draw(&Circle {
+            x,
+            y,
+            radius: 2.0,
+            color: Color::Magenta,
+        });
+    }
+    ctx.draw(&Rectangle {
+        x: game.basket_x() - CatchGame::BASKET_HALF_WIDTH,
+        y: CatchGame::BASKET_Y - 3.0,
+        width: CatchGame::BASKET_HALF_WIDTH * 2.0,
+        height: 3.0,
+        color: Color::Yellow,
+    });
+    ctx.print(
+        PLAYGROUND.left + 5.0,
+        PLAYGROUND.top - 5.0,
+        format!("Caught {}/{}", game.caught(), CatchGame::TREATS),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_map_into_the_playground() {
+        let area = Rect::new(10, 5, 100, 50);
+        assert_eq!(to_playground(area, PLAYGROUND, 9, 5), None);
+        assert_eq!(to_playground(area, PLAYGROUND, 10, 55), None);
+        let (x, y) = to_playground(area, PLAYGROUND, 10, 5).unwrap();
+        assert_eq!((x, y), (PLAYGROUND.left + 1.0, PLAYGROUND.top - 1.0));
+        let (x, y) = to_playground(area, PLAYGROUND, 109, 54).unwrap();
+        assert_eq!((x, y), (PLAYGROUND.right - 1.0, PLAYGROUND.bottom + 1.0));
+    }
+
+    #[test]
+    fn playground_matches_the_canvas_shape() {
+        assert_eq!(playground_for(Rect::new(0, 0, 100, 25)), PLAYGROUND);
+        let wide = playground_for(Rect::new(0, 0, 200, 25));
+        assert_eq!(wide.width(), 2.0 * PLAYGROUND.width());
+        assert_eq!(wide.height(), PLAYGROUND.height());
+    }
+}
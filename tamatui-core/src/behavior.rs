@@ -0,0 +1,371 @@
+//! What the pet does with itself when nobody is telling it what to do.
+//!
+//! Every so often the pet picks an [`Activity`] at random, weighted by its needs: a
+//! hungry pet heads for its bowl, a tired one lies down and a bored one goes after
+//! its toy. Rather than jumping around, the pet's velocity eases towards wherever
+//! it wants to go, and it bounces off the edges of the playground.
+
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{food::Food, personality, pet::Pet, world::Bounds};
+
+/// How quickly the pet's velocity catches up with the velocity it wants, per second.
+const EASING: f64 = 3.0;
+/// The pet starts slowing down this far from its target.
+const SLOW_RADIUS: f64 = 20.0;
+/// Close enough to a target to count as having reached it.
+const ARRIVE_DISTANCE: f64 = 3.0;
+/// Fraction of its top speed the pet uses when just wandering around.
+const WANDER_PACE: f64 = 0.5;
+/// Seeking gives up after this long, in case the target can't be reached.
+const SEEK_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Something the pet can be busy with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Activity {
+    #[default]
+    Rest,
+    Wander,
+    SeekFood,
+    SeekToy,
+    Bounce,
+}
+
+impl Activity {
+    pub const ALL: [Activity; 5] = [
+        Activity::Rest,
+        Activity::Wander,
+        Activity::SeekFood,
+        Activity::SeekToy,
+        Activity::Bounce,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Activity::Rest => "Resting",
+            Activity::Wander => "Wandering",
+            Activity::SeekFood => "Looking for food",
+            Activity::SeekToy => "Fetching its toy",
+            Activity::Bounce => "Bouncing around",
+        }
+    }
+
+    /// How likely the pet is to pick this activity, relative to the others.
+    fn weight(self, pet: &Pet, items: &[Item]) -> u32 {
+        let has = |kind| items.iter().any(|item| item.kind == kind);
+        let has_food = items.iter().any(|item| item.kind.is_food());
+        match self {
+            Activity::Rest => 10 + (100 - pet.energy) / 2,
+            Activity::Wander => 30,
+            Activity::SeekFood if has_food => pet.hunger.saturating_sub(30) * 2,
+            Activity::SeekToy if has(ItemKind::Toy) && pet.energy >= 30 => {
+                (100 - pet.happiness) / 2
+            }
+            // Only a happy pet with energy to spare bounces around
+            Activity::Bounce if pet.energy >= 50 && pet.happiness >= 50 => pet.happiness / 4,
+            Activity::SeekFood | Activity::SeekToy | Activity::Bounce => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemKind {
+    Bowl,
+    Toy,
+    /// A serving dropped on the ground, eaten once the pet gets to it.
+    Food(Food),
+}
+
+impl ItemKind {
+    /// Whether the pet goes after this when hungry.
+    pub fn is_food(self) -> bool {
+        matches!(self, ItemKind::Bowl | ItemKind::Food(_))
+    }
+}
+
+/// Something lying around in the playground.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub position: (f64, f64),
+}
+
+/// The pet's current activity and how it's moving.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Behavior {
+    activity: Activity,
+    /// Where the pet is heading when wandering or seeking.
+    target: Option<(f64, f64)>,
+    /// Direction of travel when bouncing.
+    heading: (f64, f64),
+    velocity: (f64, f64),
+    /// Time left before picking something else to do.
+    remaining: Duration,
+}
+
+impl Behavior {
+    pub fn activity(&self) -> Activity {
+        self.activity
+    }
+
+    /// Current velocity in playground units per second.
+    pub fn velocity(&self) -> (f64, f64) {
+        self.velocity
+    }
+
+    /// Sends the pet straight for `target`, dropping whatever it was doing.
+    pub(crate) fn seek(&mut self, activity: Activity, target: (f64, f64)) {
+        self.activity = activity;
+        self.target = Some(target);
+        self.remaining = SEEK_TIMEOUT;
+    }
+
+    /// Follows a target that was moved from `from` to `to`.
+    pub(crate) fn retarget(&mut self, from: (f64, f64), to: (f64, f64)) {
+        if self.target == Some(from) {
+            self.target = Some(to);
+        }
+    }
+
+    /// Keeps heading for the same spot after the playground changed shape.
+    pub(crate) fn rescale(&mut self, from: Bounds, to: Bounds) {
+        self.target = self.target.map(|target| from.map_to(to, target));
+    }
+
+    /// Moves the pet for a single slice of simulated time, picking a new activity
+    /// when the current one is over.
+    pub(crate) fn step<R: Rng + ?Sized>(
+        &mut self,
+        pet: &mut Pet,
+        items: &[Item],
+        bounds: Bounds,
+        dt: Duration,
+        rng: &mut R,
+    ) {
+        let stage = pet.stage();
+        if !stage.can_move() || pet.asleep {
+            *self = Behavior::default();
+            return;
+        }
+
+        self.remaining = self.remaining.saturating_sub(dt);
+        let arrived = self
+            .target
+            .is_some_and(|target| distance(pet.position, target) < ARRIVE_DISTANCE);
+        if arrived && matches!(self.activity, Activity::SeekFood | Activity::SeekToy) {
+            // Sit down next to whatever it was after
+            self.activity = Activity::Rest;
+            self.target = None;
+            self.remaining = Duration::from_secs(rng.random_range(2..5));
+        } else if self.remaining.is_zero() {
+            self.choose(pet, items, bounds, rng);
+        }
+
+        let speed = stage.speed();
+        let desired = match self.activity {
+            Activity::Rest => (0.0, 0.0),
+            Activity::Bounce => (self.heading.0 * speed, self.heading.1 * speed),
+            Activity::Wander | Activity::SeekFood | Activity::SeekToy => {
+                let pace = if self.activity == Activity::Wander {
+                    WANDER_PACE
+                } else {
+                    1.0
+                };
+                self.target.map_or((0.0, 0.0), |target| {
+                    arrive(pet.position, target, speed * pace, dt)
+                })
+            }
+        };
+
+        let secs = dt.as_secs_f64();
+        let ease = (EASING * secs).min(1.0);
+        self.velocity.0 += (desired.0 - self.velocity.0) * ease;
+        self.velocity.1 += (desired.1 - self.velocity.1) * ease;
+
+        let x = pet.position.0 + self.velocity.0 * secs;
+        let y = pet.position.1 + self.velocity.1 * secs;
+        let (clamped_x, clamped_y) = pet.clamped(x, y, bounds);
+        // Bounce off the walls
+        if clamped_x != x {
+            self.velocity.0 = -self.velocity.0;
+            self.heading.0 = -self.heading.0;
+        }
+        if clamped_y != y {
+            self.velocity.1 = -self.velocity.1;
+            self.heading.1 = -self.heading.1;
+        }
+        pet.position = (clamped_x, clamped_y);
+    }
+
+    fn choose<R: Rng + ?Sized>(&mut self, pet: &Pet, items: &[Item], bounds: Bounds, rng: &mut R) {
+        let weights = Activity::ALL.map(|activity| {
+            personality::activity_weight(activity, activity.weight(pet, items), &pet.traits)
+        });
+        let mut pick = rng.random_range(0..weights.iter().sum::<u32>());
+        let mut activity = Activity::Rest;
+        for (candidate, weight) in Activity::ALL.into_iter().zip(weights) {
+            if pick < weight {
+                activity = candidate;
+                break;
+            }
+            pick -= weight;
+        }
+
+        let find = |kind| {
+            items
+                .iter()
+                .find(|item| item.kind == kind)
+                .map(|item| item.position)
+        };
+        self.activity = activity;
+        self.target = None;
+        self.remaining = match activity {
+            Activity::Rest => Duration::from_secs(rng.random_range(3..8)),
+            Activity::Wander => {
+                let (x, y) = (
+                    rng.random_range(bounds.left..=bounds.right),
+                    rng.random_range(bounds.bottom..=bounds.top),
+                );
+                self.target = Some(pet.clamped(x, y, bounds));
+                Duration::from_secs(rng.random_range(4..10))
+            }
+            Activity::SeekFood => {
+                // Food on the ground beats an empty bowl
+                self.target = items
+                    .iter()
+                    .find(|item| matches!(item.kind, ItemKind::Food(_)))
+                    .map(|item| item.position)
+                    .or_else(|| find(ItemKind::Bowl));
+                SEEK_TIMEOUT
+            }
+            Activity::SeekToy => {
+                self.target = find(ItemKind::Toy);
+                SEEK_TIMEOUT
+            }
+            Activity::Bounce => {
+                let angle = rng.random_range(0.0..std::f64::consts::TAU);
+                self.heading = (angle.cos(), angle.sin());
+                Duration::from_secs(rng.random_range(3..6))
+            }
+        };
+    }
+}
+
+pub(crate) fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Velocity towards `to` at up to `speed`, slowing down on the final approach so
+/// the pet doesn't overshoot within `dt`.
+fn arrive(from: (f64, f64), to: (f64, f64), speed: f64, dt: Duration) -> (f64, f64) {
+    let distance = distance(from, to);
+    if distance < f64::EPSILON {
+        return (0.0, 0.0);
+    }
+    let speed = (speed * (distance / SLOW_RADIUS).min(1.0)).min(distance / dt.as_secs_f64());
+    (
+        (to.0 - from.0) / distance * speed,
+        (to.1 - from.1) / distance * speed,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{stage::Stage, world::PLAYGROUND};
+
+    const BOWL: Item = Item {
+        kind: ItemKind::Bowl,
+        position: (20.0, 20.0),
+    };
+
+    fn child() -> Pet {
+        let mut pet = Pet::new();
+        pet.age = Duration::from_secs(20 * 60);
+        pet
+    }
+
+    #[test]
+    fn starving_pet_goes_to_its_bowl() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pet = child();
+        pet.hunger = 100;
+        pet.energy = 100;
+        pet.happiness = 40;
+        let mut behavior = Behavior::default();
+        let seen: Vec<Activity> = (0..300)
+            .map(|_| {
+                let dt = Duration::from_millis(100);
+                behavior.step(&mut pet, &[BOWL], PLAYGROUND, dt, &mut rng);
+                behavior.activity()
+            })
+            .collect();
+        assert!(seen.contains(&Activity::SeekFood));
+        assert!(distance(pet.position, BOWL.position) < 40.0);
+    }
+
+    #[test]
+    fn velocity_eases_instead_of_jumping() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pet = child();
+        let mut behavior = Behavior {
+            activity: Activity::Wander,
+            target: Some((200.0, 50.0)),
+            remaining: Duration::from_secs(10),
+            ..Behavior::default()
+        };
+        let dt = Duration::from_millis(16);
+        behavior.step(&mut pet, &[], PLAYGROUND, dt, &mut rng);
+        let first = behavior.velocity().0;
+        behavior.step(&mut pet, &[], PLAYGROUND, dt, &mut rng);
+        assert!(first > 0.0);
+        assert!(first < Stage::Child.speed() * WANDER_PACE / 2.0);
+        assert!(behavior.velocity().0 > first);
+    }
+
+    #[test]
+    fn bouncing_pet_turns_around_at_the_walls() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pet = child();
+        pet.position = (PLAYGROUND.left + 1.0, 50.0);
+        let mut behavior = Behavior {
+            activity: Activity::Bounce,
+            heading: (-1.0, 0.0),
+            velocity: (-30.0, 0.0),
+            remaining: Duration::from_secs(10),
+            ..Behavior::default()
+        };
+        behavior.step(
+            &mut pet,
+            &[],
+            PLAYGROUND,
+            Duration::from_millis(100),
+            &mut rng,
+        );
+        assert_eq!(pet.position.0, PLAYGROUND.left);
+        assert!(behavior.velocity().0 > 0.0);
+    }
+
+    #[test]
+    fn eggs_stay_put() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pet = Pet::new();
+        let start = pet.position;
+        let mut behavior = Behavior::default();
+        for _ in 0..100 {
+            behavior.step(
+                &mut pet,
+                &[BOWL],
+                PLAYGROUND,
+                Duration::from_secs(1),
+                &mut rng,
+            );
+        }
+        assert_eq!(pet.position, start);
+    }
+}
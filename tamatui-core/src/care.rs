@@ -0,0 +1,80 @@
+//! Calls for attention, and the care mistakes left behind when nobody answers.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{pet::Pet, stage::Stage};
+
+/// A call left unanswered this long counts as a care mistake.
+pub const CALL_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Babies only stay babies for a few minutes, so their calls run out sooner.
+pub const BABY_CALL_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+/// The pet calls for food once hunger reaches this.
+pub const CALL_HUNGER: u32 = 80;
+/// The pet calls for company once happiness drops to this.
+pub const CALL_HAPPINESS: u32 = 20;
+/// Happiness gained from being petted.
+pub const STROKE_REWARD: u32 = 3;
+/// Simulated time before petting has any effect again.
+pub const STROKE_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Something the pet needs its owner for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Need {
+    Food,
+    Company,
+}
+
+impl Need {
+    pub fn name(self) -> &'static str {
+        match self {
+            Need::Food => "Hungry",
+            Need::Company => "Lonely",
+        }
+    }
+
+    /// Whether the pet still needs this.
+    pub fn is_pressing(self, pet: &Pet) -> bool {
+        match self {
+            Need::Food => pet.hunger >= CALL_HUNGER,
+            Need::Company => pet.happiness <= CALL_HAPPINESS,
+        }
+    }
+}
+
+/// The pet calling for its owner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Call {
+    pub need: Need,
+    /// Age at which the call started.
+    pub since: Duration,
+    /// Nobody came in time; the pet has given up calling.
+    pub ignored: bool,
+}
+
+impl Call {
+    /// The pet is still actively calling.
+    pub fn is_open(&self) -> bool {
+        !self.ignored
+    }
+}
+
+/// How long a pet at `stage` keeps calling before it gives up and the call counts
+/// as a care mistake.
+pub fn call_timeout(stage: Stage) -> Duration {
+    match stage {
+        Stage::Baby => BABY_CALL_TIMEOUT,
+        _ => CALL_TIMEOUT,
+    }
+}
+
+/// The most pressing thing the pet would call about right now.
+pub fn pressing_need(pet: &Pet) -> Option<Need> {
+    if !pet.stage().can_feed() {
+        return None;
+    }
+    [Need::Food, Need::Company]
+        .into_iter()
+        .find(|need| need.is_pressing(pet))
+}
@@ -0,0 +1,145 @@
+//! Simulation time, kept separate from how often the screen is redrawn.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// The longest slice of time the simulation advances in one step.
+///
+/// Larger gaps (a stalled terminal, time spent offline, fast-forward) are split into
+/// steps of this size so stage changes and deadlines are never skipped over.
+pub const MAX_STEP: Duration = Duration::from_secs(1);
+
+/// How much faster than real time the simulation runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeScale {
+    #[default]
+    X1,
+    X10,
+    X100,
+    X1000,
+}
+
+impl TimeScale {
+    pub fn factor(self) -> u32 {
+        match self {
+            TimeScale::X1 => 1,
+            TimeScale::X10 => 10,
+            TimeScale::X100 => 100,
+            TimeScale::X1000 => 1000,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            TimeScale::X1 => TimeScale::X10,
+            TimeScale::X10 => TimeScale::X100,
+            TimeScale::X100 => TimeScale::X1000,
+            TimeScale::X1000 => TimeScale::X1,
+        }
+    }
+
+    /// Converts an amount of real time into simulated time.
+    pub fn scale(self, real: Duration) -> Duration {
+        real * self.factor()
+    }
+}
+
+/// Simulated time advanced by a single step while paused.
+pub const STEP: Duration = MAX_STEP;
+
+/// Turns real time into simulated time, and keeps track of both.
+///
+/// Time can be sped up, paused, and stepped through a [`STEP`] at a time while
+/// paused.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimClock {
+    pub scale: TimeScale,
+    pub paused: bool,
+    /// Real time the clock has been running, paused or not.
+    pub real: Duration,
+    /// Simulated time that has passed.
+    pub simulated: Duration,
+    /// Steps asked for but not taken yet.
+    steps: u32,
+}
+
+impl SimClock {
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Pauses and queues up one [`STEP`] for the next [`SimClock::advance`].
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.steps += 1;
+    }
+
+    /// Moves the clock on by `real` time and returns how much simulated time
+    /// passed. Unless `scaled`, time runs as fast as in the real world.
+    pub fn advance(&mut self, real: Duration, scaled: bool) -> Duration {
+        self.real += real;
+        let simulated = if self.paused {
+            STEP * std::mem::take(&mut self.steps)
+        } else if scaled {
+            self.scale.scale(real)
+        } else {
+            real
+        };
+        self.simulated += simulated;
+        simulated
+    }
+}
+
+/// Counts how many whole intervals have passed as time is fed in.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Every {
+    elapsed: Duration,
+}
+
+impl Every {
+    /// Adds `dt` and returns how many times `interval` has elapsed since the last call.
+    pub fn tick(&mut self, dt: Duration, interval: Duration) -> u32 {
+        self.elapsed += dt;
+        let mut count = 0;
+        while self.elapsed >= interval {
+            self.elapsed -= interval;
+            count += 1;
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_carries_over_partial_intervals() {
+        let mut every = Every::default();
+        let interval = Duration::from_millis(100);
+        assert_eq!(every.tick(Duration::from_millis(60), interval), 0);
+        assert_eq!(every.tick(Duration::from_millis(60), interval), 1);
+        assert_eq!(every.tick(Duration::from_millis(280), interval), 3);
+    }
+
+    #[test]
+    fn paused_clocks_only_step() {
+        let mut clock = SimClock {
+            scale: TimeScale::X10,
+            ..SimClock::default()
+        };
+        let frame = Duration::from_millis(100);
+        assert_eq!(clock.advance(frame, true), Duration::from_secs(1));
+        assert_eq!(clock.advance(frame, false), frame);
+        clock.toggle_pause();
+        assert_eq!(clock.advance(frame, true), Duration::ZERO);
+        clock.step();
+        clock.step();
+        assert_eq!(clock.advance(frame, true), STEP * 2);
+        assert_eq!(clock.advance(frame, true), Duration::ZERO);
+        assert!(clock.paused);
+        assert_eq!(clock.real, frame * 5);
+        assert_eq!(clock.simulated, Duration::from_secs(1) + frame + STEP * 2);
+    }
+}
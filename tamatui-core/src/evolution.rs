@@ -0,0 +1,255 @@
+//! How well the pet is looked after, and the form it grows into because of it.
+//!
+//! Every stage keeps a [`CareRecord`]. When the pet grows into a child, teen or
+//! adult, the record for the stage it's leaving picks which branch of the
+//! evolution tree it takes, like the original Tamagotchi. Elders keep their adult
+//! form.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    care::{CALL_HAPPINESS, CALL_HUNGER},
+    mood::SICK_BELOW,
+    pet::Pet,
+    stage::Stage,
+};
+
+/// How the pet was looked after during its current stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CareRecord {
+    /// Time spent in the stage so far.
+    pub time: Duration,
+    /// Time spent very hungry, very unhappy or in poor health.
+    pub danger: Duration,
+    /// Calls that went unanswered.
+    pub mistakes: u32,
+    pub meals: u32,
+    pub treats: u32,
+    /// Times the pet was fed while already full.
+    pub overfeeds: u32,
+}
+
+/// Overall verdict on a [`CareRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quality {
+    Poor,
+    Good,
+    Great,
+}
+
+/// Whether the pet's needs are in the danger zone right now.
+pub fn in_danger(pet: &Pet) -> bool {
+    pet.hunger >= CALL_HUNGER || pet.happiness <= CALL_HAPPINESS || pet.health < SICK_BELOW
+}
+
+impl Quality {
+    pub fn name(self) -> &'static str {
+        match self {
+            Quality::Poor => "Poor",
+            Quality::Good => "Good",
+            Quality::Great => "Great",
+        }
+    }
+}
+
+impl CareRecord {
+    /// Share of the stage spent in the danger zone, from 0 to 1.
+    pub fn danger_share(&self) -> f64 {
+        if self.time.is_zero() {
+            return 0.0;
+        }
+        self.danger.as_secs_f64() / self.time.as_secs_f64()
+    }
+
+    pub fn quality(&self) -> Quality {
+        let share = self.danger_share();
+        if self.mistakes == 0 && share < 0.1 && self.overfeeds <= 1 {
+            Quality::Great
+        } else if self.mistakes <= 2 && share < 0.3 {
+            Quality::Good
+        } else {
+            Quality::Poor
+        }
+    }
+
+    /// More treats than proper meals.
+    pub fn junk_diet(&self) -> bool {
+        self.treats > self.meals
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Form {
+    Babblet,
+    Sprout,
+    Scamp,
+    Star,
+    Rascal,
+    Slouch,
+    Angel,
+    Sage,
+    Glutton,
+    Rebel,
+}
+
+impl Form {
+    pub const ALL: [Form; 10] = [
+        Form::Babblet,
+        Form::Sprout,
+        Form::Scamp,
+        Form::Star,
+        Form::Rascal,
+        Form::Slouch,
+        Form::Angel,
+        Form::Sage,
+        Form::Glutton,
+        Form::Rebel,
+    ];
+
+    /// Every baby starts out as this.
+    pub const HATCHLING: Form = Form::Babblet;
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Form::Babblet => "Babblet",
+            Form::Sprout => "Sprout",
+            Form::Scamp => "Scamp",
+            Form::Star => "Star",
+            Form::Rascal => "Rascal",
+            Form::Slouch => "Slouch",
+            Form::Angel => "Angel",
+            Form::Sage => "Sage",
+            Form::Glutton => "Glutton",
+            Form::Rebel => "Rebel",
+        }
+    }
+
+    /// What it takes to grow into this form.
+    pub fn hint(self) -> &'static str {
+        match self {
+            Form::Babblet => "Hatch an egg",
+            Form::Sprout => "Raise a baby without care mistakes",
+            Form::Scamp => "Let a baby's calls go unanswered",
+            Form::Star => "Raise a Sprout with great care",
+            Form::Rascal => "Raise a child with so-so care",
+            Form::Slouch => "Neglect a Scamp",
+            Form::Angel => "Raise a Star with great care and a healthy diet",
+            Form::Sage => "Raise a Star or Rascal with decent care",
+            Form::Glutton => "Feed a teen more treats than meals",
+            Form::Rebel => "Neglect a Rascal, or raise a Slouch",
+        }
+    }
+
+    /// The stage this form belongs to. Elders keep their adult form.
+    pub fn stage(self) -> Stage {
+        match self {
+            Form::Babblet => Stage::Baby,
+            Form::Sprout | Form::Scamp => Stage::Child,
+            Form::Star | Form::Rascal | Form::Slouch => Stage::Teen,
+            Form::Angel | Form::Sage | Form::Glutton | Form::Rebel => Stage::Adult,
+        }
+    }
+
+    /// Forms this one can grow into.
+    pub fn branches(self) -> &'static [Form] {
+        match self {
+            Form::Babblet => &[Form::Sprout, Form::Scamp],
+            Form::Sprout => &[Form::Star, Form::Rascal],
+            Form::Scamp => &[Form::Rascal, Form::Slouch],
+            Form::Star => &[Form::Angel, Form::Sage, Form::Glutton],
+            Form::Rascal => &[Form::Sage, Form::Glutton, Form::Rebel],
+            Form::Slouch => &[Form::Glutton, Form::Rebel],
+            Form::Angel | Form::Sage | Form::Glutton | Form::Rebel => &[],
+        }
+    }
+
+    /// The form a pet grows into after a stage looked after as in `care`.
+    ///
+    /// Returns `self` for forms with no branches left.
+    pub fn next(self, care: &CareRecord) -> Form {
+        match (self, care.quality()) {
+            (Form::Babblet, _) if care.mistakes == 0 => Form::Sprout,
+            (Form::Babblet, _) => Form::Scamp,
+            (Form::Sprout, Quality::Great) => Form::Star,
+            (Form::Sprout, _) => Form::Rascal,
+            (Form::Scamp, Quality::Poor) => Form::Slouch,
+            (Form::Scamp, _) => Form::Rascal,
+            (Form::Star | Form::Rascal | Form::Slouch, _) if care.junk_diet() => Form::Glutton,
+            (Form::Star, Quality::Great) => Form::Angel,
+            (Form::Star, _) => Form::Sage,
+            (Form::Rascal, Quality::Poor) | (Form::Slouch, _) => Form::Rebel,
+            (Form::Rascal, _) => Form::Sage,
+            (Form::Angel | Form::Sage | Form::Glutton | Form::Rebel, _) => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(mistakes: u32, danger_secs: u64) -> CareRecord {
+        CareRecord {
+            time: Duration::from_secs(100),
+            danger: Duration::from_secs(danger_secs),
+            mistakes,
+            ..CareRecord::default()
+        }
+    }
+
+    #[test]
+    fn care_picks_the_branch() {
+        assert_eq!(Form::Babblet.next(&record(0, 50)), Form::Sprout);
+        assert_eq!(Form::Babblet.next(&record(1, 0)), Form::Scamp);
+        assert_eq!(Form::Sprout.next(&record(0, 0)), Form::Star);
+        assert_eq!(Form::Scamp.next(&record(5, 0)), Form::Slouch);
+        assert_eq!(Form::Star.next(&record(0, 0)), Form::Angel);
+        assert_eq!(Form::Rascal.next(&record(4, 80)), Form::Rebel);
+        let junk = CareRecord {
+            treats: 5,
+            meals: 1,
+            ..record(0, 0)
+        };
+        assert_eq!(Form::Star.next(&junk), Form::Glutton);
+        assert_eq!(Form::Angel.next(&record(9, 100)), Form::Angel);
+    }
+
+    #[test]
+    fn every_branch_is_reachable_and_a_stage_later() {
+        for form in Form::ALL {
+            for branch in form.branches() {
+                assert!(branch.stage() > form.stage(), "{form:?} -> {branch:?}");
+            }
+            if form != Form::HATCHLING {
+                assert!(
+                    Form::ALL.iter().any(|f| f.branches().contains(&form)),
+                    "{form:?} can't be reached"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn next_stays_on_the_tree() {
+        let records = [record(0, 0), record(1, 20), record(3, 50), record(9, 100)];
+        for form in Form::ALL {
+            for care in &records {
+                for junk in [false, true] {
+                    let care = CareRecord {
+                        treats: if junk { 9 } else { 0 },
+                        ..*care
+                    };
+                    let next = form.next(&care);
+                    assert!(
+                        next == form && form.branches().is_empty()
+                            || form.branches().contains(&next),
+                        "{form:?} -> {next:?}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Things the pet can eat.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// A food the owner can pick from the feed menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Food {
+    Meal,
+    Snack,
+    Treat,
+}
+
+/// How a single serving changes the pet's stats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoodEffect {
+    pub hunger: i32,
+    pub happiness: i32,
+    pub weight: i32,
+    pub health: i32,
+}
+
+/// Feeding while hunger is at or below this counts as overfeeding.
+pub const FULL_THRESHOLD: u32 = 10;
+
+/// Extra effect applied on top of the food's own effect when the pet is overfed.
+pub const OVERFEED_PENALTY: FoodEffect = FoodEffect {
+    hunger: 0,
+    happiness: -10,
+    weight: 2,
+    health: -10,
+};
+
+impl Food {
+    pub const ALL: [Food; 3] = [Food::Meal, Food::Snack, Food::Treat];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Food::Meal => "Meal",
+            Food::Snack => "Snack",
+            Food::Treat => "Treat",
+        }
+    }
+
+    pub fn effect(self) -> FoodEffect {
+        match self {
+            // Filling and healthy, but not very exciting
+            Food::Meal => FoodEffect {
+                hunger: -40,
+                happiness: 5,
+                weight: 2,
+                health: 5,
+            },
+            Food::Snack => FoodEffect {
+                hunger: -15,
+                happiness: 10,
+                weight: 1,
+                health: 0,
+            },
+            // Makes the pet very happy, at a cost
+            Food::Treat => FoodEffect {
+                hunger: -5,
+                happiness: 25,
+                weight: 3,
+                health: -5,
+            },
+        }
+    }
+
+    /// How long the pet refuses this food after eating it.
+    pub fn cooldown(self) -> Duration {
+        match self {
+            Food::Meal => Duration::from_secs(30),
+            Food::Snack => Duration::from_secs(10),
+            Food::Treat => Duration::from_secs(60),
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Food::Meal => 0,
+            Food::Snack => 1,
+            Food::Treat => 2,
+        }
+    }
+}
+
+/// Applies a signed change to a stat, keeping it within `0..=max`.
+pub fn adjust(stat: u32, delta: i32, max: u32) -> u32 {
+    stat.saturating_add_signed(delta).min(max)
+}
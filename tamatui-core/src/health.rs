@@ -0,0 +1,99 @@
+//! Illnesses, what causes them and how they wear the pet down.
+
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{hygiene::WASTE_RISK, pet::Pet, stage::Stage};
+
+/// Simulated time between each roll for whether the pet falls ill.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Simulated time between each point of health recovered while well.
+pub const RECOVERY_INTERVAL: Duration = Duration::from_secs(60);
+/// Happiness lost to the bitter taste of medicine.
+pub const MEDICINE_PENALTY: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Illness {
+    /// Caught from living in a dirty playground.
+    Cold,
+    /// From going hungry for too long.
+    Tummyache,
+    /// Comes with being very young or very old.
+    Fever,
+}
+
+impl Illness {
+    pub const ALL: [Illness; 3] = [Illness::Cold, Illness::Tummyache, Illness::Fever];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Illness::Cold => "Cold",
+            Illness::Tummyache => "Tummyache",
+            Illness::Fever => "Fever",
+        }
+    }
+
+    /// Simulated time between each point of health lost while the illness is untreated.
+    pub fn drain_interval(self) -> Duration {
+        let secs = match self {
+            Illness::Cold => 20,
+            Illness::Tummyache => 12,
+            Illness::Fever => 8,
+        };
+        Duration::from_secs(secs)
+    }
+}
+
+/// Chances of falling ill at the next check, in tenths of a percent, for each illness.
+pub fn risks(pet: &Pet) -> [(Illness, u32); 3] {
+    let age = match pet.stage() {
+        Stage::Baby => 20,
+        Stage::Elder => 40,
+        _ => 5,
+    };
+    [
+        (
+            Illness::Cold,
+            60u32.saturating_sub(pet.cleanliness) * 2 + pet.waste.len() as u32 * WASTE_RISK,
+        ),
+        (Illness::Tummyache, pet.hunger.saturating_sub(60) * 3),
+        (Illness::Fever, age),
+    ]
+}
+
+/// Rolls for whether the pet falls ill, and with what.
+pub(crate) fn roll<R: Rng + ?Sized>(pet: &Pet, rng: &mut R) -> Option<Illness> {
+    let risks = risks(pet);
+    let mut pick = rng.random_range(0..1000);
+    for (illness, chance) in risks {
+        if pick < chance {
+            return Some(illness);
+        }
+        pick -= chance;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn neglect_raises_the_risk() {
+        let mut pet = Pet::new();
+        pet.age = Duration::from_secs(20 * 60);
+        let total = |pet: &Pet| risks(pet).iter().map(|(_, chance)| chance).sum::<u32>();
+        let healthy = total(&pet);
+        pet.hunger = 100;
+        pet.cleanliness = 0;
+        assert!(total(&pet) > healthy * 10);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let ill = (0..100).filter_map(|_| roll(&pet, &mut rng)).count();
+        assert!(ill > 10);
+    }
+}
@@ -0,0 +1,14 @@
+//! Droppings and keeping the playground clean.
+
+use std::time::Duration;
+
+/// Simulated time between eating and the resulting mess.
+pub const DIGESTION_TIME: Duration = Duration::from_secs(2 * 60);
+/// Cleanliness lost straight away each time the pet makes a mess.
+pub const MESS_PENALTY: u32 = 10;
+/// Simulated time it takes each pile of waste to cost a point of cleanliness.
+pub const DIRT_INTERVAL: Duration = Duration::from_secs(20);
+/// The playground never holds more piles than this; the pet just gets dirtier.
+pub const MAX_WASTE: usize = 8;
+/// Extra chance of catching a cold for each pile lying around, in tenths of a percent.
+pub const WASTE_RISK: u32 = 10;
@@ -0,0 +1,25 @@
+//! The rules of tamatui, without any user interface.
+//!
+//! A [`World`] holds a [`Pet`] and the playground it lives in. Frontends feed it
+//! player actions and call [`World::step`] with the time that has passed; it
+//! reports what happened as [`Event`]s. All randomness comes from the world's own
+//! seeded RNG, so a world started from the same seed and given the same inputs
+//! always ends up in the same state.
+
+pub mod behavior;
+pub mod care;
+pub mod clock;
+pub mod evolution;
+pub mod food;
+pub mod health;
+pub mod hygiene;
+pub mod mood;
+pub mod personality;
+pub mod pet;
+pub mod play;
+pub mod shop;
+pub mod stage;
+pub mod world;
+
+pub use pet::{Fed, Pet, Refusal};
+pub use world::{Bounds, Event, SimRng, World};
@@ -0,0 +1,68 @@
+//! How the pet is feeling, derived from its stats.
+
+use crate::pet::Pet;
+
+/// Health below this makes the pet look sick, as does any illness.
+pub const SICK_BELOW: u32 = 30;
+/// Hunger at or above this makes the pet look hungry.
+pub const HUNGRY_FROM: u32 = 70;
+/// Happiness below this makes the pet look sad.
+pub const SAD_BELOW: u32 = 30;
+/// Happiness at or above this makes the pet look happy.
+pub const HAPPY_FROM: u32 = 70;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mood {
+    Happy,
+    Content,
+    Sad,
+    Hungry,
+    Sick,
+}
+
+impl Mood {
+    /// The most pressing feeling the pet has right now.
+    pub fn of(pet: &Pet) -> Self {
+        if pet.illness.is_some() || pet.health < SICK_BELOW {
+            Mood::Sick
+        } else if pet.hunger >= HUNGRY_FROM {
+            Mood::Hungry
+        } else if pet.happiness < SAD_BELOW {
+            Mood::Sad
+        } else if pet.happiness >= HAPPY_FROM {
+            Mood::Happy
+        } else {
+            Mood::Content
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mood::Happy => "Happy",
+            Mood::Content => "Content",
+            Mood::Sad => "Sad",
+            Mood::Hungry => "Hungry",
+            Mood::Sick => "Sick",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sickness_outranks_other_feelings() {
+        let mut pet = Pet::new();
+        pet.health = 10;
+        pet.hunger = 90;
+        assert_eq!(Mood::of(&pet), Mood::Sick);
+        pet.health = 100;
+        assert_eq!(Mood::of(&pet), Mood::Hungry);
+        pet.hunger = 0;
+        pet.happiness = 10;
+        assert_eq!(Mood::of(&pet), Mood::Sad);
+        pet.happiness = 50;
+        assert_eq!(Mood::of(&pet), Mood::Content);
+    }
+}
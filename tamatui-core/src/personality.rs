@@ -0,0 +1,217 @@
+//! Hidden personality traits, misbehaving and discipline.
+//!
+//! Every pet hatches with one or two [`Trait`]s that the owner has to figure out from
+//! how it acts. Pets with little discipline act up now and then; scolding them when
+//! they do, and praising them when they don't, teaches them to behave.
+
+use std::{fmt, time::Duration};
+
+use rand::{seq::IndexedRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    behavior::Activity,
+    food::{Food, FoodEffect},
+};
+
+/// Simulated time between each roll for whether the pet starts misbehaving.
+pub const MISCHIEF_INTERVAL: Duration = Duration::from_secs(2 * 60);
+/// Misbehaving stops by itself after this long, whether or not anyone noticed.
+pub const MISCHIEF_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+/// Discipline gained from a deserved scolding, or lost from praising bad behavior.
+pub const DISCIPLINE_STEP: u32 = 10;
+/// Happiness lost when scolded, doubled if the pet did nothing wrong.
+pub const SCOLD_PENALTY: u32 = 5;
+/// Happiness gained from deserved praise.
+pub const PRAISE_REWARD: u32 = 5;
+/// Simulated time before praise has any effect again.
+pub const PRAISE_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Trait {
+    /// Gets bored quickly and loves chasing its toy.
+    Playful,
+    /// Rests a lot and burns through food slowly.
+    Lazy,
+    /// Turns its nose up at plain meals but adores treats.
+    Picky,
+    /// Sulks easily, hates being woken and acts up more often.
+    Grumpy,
+}
+
+impl Trait {
+    pub const ALL: [Trait; 4] = [Trait::Playful, Trait::Lazy, Trait::Picky, Trait::Grumpy];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Trait::Playful => "Playful",
+            Trait::Lazy => "Lazy",
+            Trait::Picky => "Picky",
+            Trait::Grumpy => "Grumpy",
+        }
+    }
+}
+
+/// One or two different traits for a newly hatched pet.
+pub(crate) fn pick<R: Rng + ?Sized>(rng: &mut R) -> Vec<Trait> {
+    let count = rng.random_range(1..=2);
+    let mut traits: Vec<Trait> = Trait::ALL.choose_multiple(rng, count).copied().collect();
+    traits.sort_by_key(|t| Trait::ALL.iter().position(|other| other == t));
+    traits
+}
+
+/// Stretches or shrinks a decay interval according to the pet's traits.
+fn scaled(interval: Duration, traits: &[Trait], percent: impl Fn(Trait) -> u32) -> Duration {
+    traits
+        .iter()
+        .fold(interval, |interval, &t| interval * percent(t) / 100)
+}
+
+pub fn hunger_interval(interval: Duration, traits: &[Trait]) -> Duration {
+    scaled(interval, traits, |t| match t {
+        Trait::Lazy => 125,
+        _ => 100,
+    })
+}
+
+pub fn happiness_interval(interval: Duration, traits: &[Trait]) -> Duration {
+    scaled(interval, traits, |t| match t {
+        Trait::Playful => 75,
+        Trait::Grumpy => 80,
+        _ => 100,
+    })
+}
+
+/// How the pet's traits change what it gets out of a food.
+pub fn food_effect(food: Food, traits: &[Trait]) -> FoodEffect {
+    let mut effect = food.effect();
+    if traits.contains(&Trait::Picky) {
+        match food {
+            Food::Meal => effect.happiness = -5,
+            Food::Treat => effect.happiness *= 2,
+            Food::Snack => {}
+        }
+    }
+    effect
+}
+
+/// Scales how likely the pet is to pick `activity` according to its traits.
+pub fn activity_weight(activity: Activity, weight: u32, traits: &[Trait]) -> u32 {
+    traits.iter().fold(weight, |weight, t| match (t, activity) {
+        (Trait::Playful, Activity::SeekToy | Activity::Bounce) => weight * 2,
+        (Trait::Lazy, Activity::Rest) => weight * 2,
+        (Trait::Lazy, Activity::Wander | Activity::Bounce) => weight / 2,
+        _ => weight,
+    })
+}
+
+/// Chance of starting to misbehave at each check, in percent.
+pub fn mischief_chance(discipline: u32, traits: &[Trait]) -> u32 {
+    let grumpy = if traits.contains(&Trait::Grumpy) {
+        10
+    } else {
+        0
+    };
+    (100 - discipline.min(100)) / 5 + grumpy
+}
+
+/// Ways the pet acts up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Misbehavior {
+    /// Won't eat anything.
+    Fussy,
+    /// Calls for attention without needing anything.
+    FakeCall,
+}
+
+impl Misbehavior {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Misbehavior::Fussy => "Refusing to eat",
+            Misbehavior::FakeCall => "Calling for no reason",
+        }
+    }
+
+    /// What the pet gets up to, with picky pets favoring fussing over food.
+    pub(crate) fn pick<R: Rng + ?Sized>(traits: &[Trait], rng: &mut R) -> Self {
+        let fussy = if traits.contains(&Trait::Picky) {
+            0.75
+        } else {
+            0.5
+        };
+        if rng.random_bool(fussy) {
+            Misbehavior::Fussy
+        } else {
+            Misbehavior::FakeCall
+        }
+    }
+}
+
+/// How the owner responds to the pet's behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    Scold,
+    Praise,
+}
+
+/// How the pet took being scolded or praised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reaction {
+    /// Scolded while misbehaving: it learned its lesson.
+    Learned,
+    /// Scolded for nothing: it's hurt.
+    Hurt,
+    /// Praised while misbehaving: it learned that acting up pays.
+    Spoiled,
+    /// Praised while behaving.
+    Pleased,
+    /// Praised again too soon to care.
+    Shrugged,
+}
+
+impl fmt::Display for Reaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reaction::Learned => write!(f, "Your pet looks sorry"),
+            Reaction::Hurt => write!(f, "Your pet didn't do anything wrong..."),
+            Reaction::Spoiled => write!(f, "Your pet thinks acting up pays off"),
+            Reaction::Pleased => write!(f, "Your pet beams with pride"),
+            Reaction::Shrugged => write!(f, "Your pet has heard that already"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn traits_are_distinct() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let traits = pick(&mut rng);
+            assert!(matches!(traits.len(), 1 | 2));
+            assert!(traits.first() != traits.get(1));
+        }
+    }
+
+    #[test]
+    fn picky_pets_prefer_treats() {
+        let picky = [Trait::Picky];
+        assert!(food_effect(Food::Meal, &picky).happiness < 0);
+        assert_eq!(
+            food_effect(Food::Treat, &picky).happiness,
+            Food::Treat.effect().happiness * 2
+        );
+        assert_eq!(food_effect(Food::Meal, &[]), Food::Meal.effect());
+    }
+
+    #[test]
+    fn discipline_curbs_mischief() {
+        assert!(mischief_chance(0, &[]) > mischief_chance(100, &[]));
+        assert_eq!(mischief_chance(100, &[]), 0);
+        assert!(mischief_chance(100, &[Trait::Grumpy]) > 0);
+    }
+}
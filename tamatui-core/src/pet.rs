@@ -0,0 +1,933 @@
+//! The pet itself: its stats and how they change over time.
+
+use std::{fmt, time::Duration};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    care::{self, Call, Need, CALL_HAPPINESS, CALL_HUNGER, STROKE_COOLDOWN, STROKE_REWARD},
+    clock::Every,
+    evolution::{self, CareRecord, Form},
+    food::{self, Food, FULL_THRESHOLD, OVERFEED_PENALTY},
+    health::{self, Illness, MEDICINE_PENALTY},
+    hygiene::{DIGESTION_TIME, DIRT_INTERVAL, MAX_WASTE, MESS_PENALTY},
+    mood::Mood,
+    personality::{
+        self, Misbehavior, Reaction, Response, Trait, DISCIPLINE_STEP, MISCHIEF_INTERVAL,
+        MISCHIEF_TIMEOUT, PRAISE_COOLDOWN, PRAISE_REWARD, SCOLD_PENALTY,
+    },
+    play::{GameKind, Outcome, LOSE_HAPPINESS, PLAY_ENERGY_COST, WIN_HAPPINESS},
+    shop::Product,
+    stage::{DeathCause, Stage, NEGLECT_LIMIT},
+    world::Bounds,
+};
+
+/// Space kept between the pet and the top and right edges of the playground.
+const PET_MARGIN: f64 = 5.0;
+/// Simulated time between each point of energy lost while awake.
+const TIRING_INTERVAL: Duration = Duration::from_secs(30);
+/// Simulated time between each point of energy recovered while asleep.
+const RESTING_INTERVAL: Duration = Duration::from_secs(3);
+/// The pet nods off by itself once its energy drops this low.
+pub const DROWSY_AT: u32 = 10;
+/// Hunger and happiness decay this many times slower while the pet sleeps.
+const SLEEP_SLOWDOWN: u32 = 3;
+/// Happiness lost when the pet is woken up before it's ready.
+pub const WAKE_PENALTY: u32 = 10;
+
+/// A pet and everything that happens to it over its life.
+///
+/// Stats that go from 0 to 100 are `hunger` (100 is starving), `happiness`,
+/// `health`, `energy` and `cleanliness`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pet {
+    #[serde(rename = "pet_position")]
+    pub position: (f64, f64),
+    pub hunger: u32,
+    pub happiness: u32,
+    /// Weight in grams.
+    pub weight: u32,
+    pub health: u32,
+    pub energy: u32,
+    pub cleanliness: u32,
+    pub illness: Option<Illness>,
+    pub asleep: bool,
+    /// How well behaved the pet is, from 0 to 100.
+    pub discipline: u32,
+    /// Hidden personality, picked when the egg hatches.
+    pub traits: Vec<Trait>,
+    pub misbehaving: Option<Misbehavior>,
+    /// Age at which the current misbehavior wears off.
+    pub mischief_ends_at: Duration,
+    /// Age at which praise has an effect again.
+    pub praise_ready_at: Duration,
+    /// Age at which petting has an effect again.
+    pub stroke_ready_at: Duration,
+    pub call: Option<Call>,
+    /// Calls that went unanswered over the pet's life.
+    pub care_mistakes: u32,
+    /// Which branch of the evolution tree the pet is on, once hatched.
+    pub form: Option<Form>,
+    /// How the pet has been looked after since it last grew.
+    pub care: CareRecord,
+    /// Number of simulation steps taken.
+    pub tick_count: u64,
+    /// How long the pet has been alive, including time spent offline.
+    pub age: Duration,
+    /// How long hunger has been pegged at 100.
+    pub starving_for: Duration,
+    pub cause_of_death: Option<DeathCause>,
+    /// Age at which each food comes off cooldown, indexed by [`Food::index`].
+    pub food_ready_at: [Duration; Food::ALL.len()],
+    /// Age at which the last meal comes out the other end.
+    pub mess_due: Option<Duration>,
+    /// Piles of waste lying around the playground.
+    pub waste: Vec<(f64, f64)>,
+    hunger_timer: Every,
+    happiness_timer: Every,
+    energy_timer: Every,
+    checkup_timer: Every,
+    health_timer: Every,
+    dirt_timer: Every,
+    mischief_timer: Every,
+}
+
+/// A successful feeding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fed {
+    pub food: Food,
+    /// The pet was already full and ate anyway.
+    pub overfed: bool,
+}
+
+/// Why the pet wouldn't do what it was asked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refusal {
+    Dead,
+    CantEat(Stage),
+    CantPlay(Stage, GameKind),
+    Cooldown { food: Food, remaining: Duration },
+    TooTired,
+    Asleep,
+    CantSleep(Stage),
+    Awake,
+    Sick(Illness),
+    NotSick,
+    AlreadyClean,
+    Fussy,
+    CantTrain(Stage),
+    CantStroke(Stage),
+    NoneLeft(Product),
+    CantAfford(Product),
+}
+
+/// Something that happened while stepping the pet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Change {
+    Grew { from: Stage, to: Stage },
+    Evolved(Form),
+    Died(DeathCause),
+    FellAsleep,
+    WokeUp,
+    FellIll(Illness),
+    MadeAMess,
+    Misbehaved(Misbehavior),
+    Called(Need),
+    CallIgnored(Need),
+}
+
+impl Default for Pet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pet {
+    /// A freshly laid egg in the middle of the default playground.
+    pub fn new() -> Self {
+        Self {
+            position: (100.0, 50.0),
+            hunger: 0,
+            happiness: 100,
+            weight: 5,
+            health: 100,
+            energy: 100,
+            cleanliness: 100,
+            illness: None,
+            asleep: false,
+            discipline: 50,
+            traits: Vec::new(),
+            misbehaving: None,
+            mischief_ends_at: Duration::ZERO,
+            praise_ready_at: Duration::ZERO,
+            stroke_ready_at: Duration::ZERO,
+            call: None,
+            care_mistakes: 0,
+            form: None,
+            care: CareRecord::default(),
+            tick_count: 0,
+            age: Duration::ZERO,
+            starving_for: Duration::ZERO,
+            cause_of_death: None,
+            food_ready_at: [Duration::ZERO; Food::ALL.len()],
+            mess_due: None,
+            waste: Vec::new(),
+            hunger_timer: Every::default(),
+            happiness_timer: Every::default(),
+            energy_timer: Every::default(),
+            checkup_timer: Every::default(),
+            health_timer: Every::default(),
+            dirt_timer: Every::default(),
+            mischief_timer: Every::default(),
+        }
+    }
+
+    pub fn stage(&self) -> Stage {
+        if self.cause_of_death.is_some() {
+            Stage::Dead
+        } else {
+            Stage::for_age(self.age)
+        }
+    }
+
+    pub fn mood(&self) -> Mood {
+        Mood::of(self)
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.cause_of_death.is_some()
+    }
+
+    /// How long until `food` can be eaten again.
+    pub fn cooldown_remaining(&self, food: Food) -> Duration {
+        self.food_ready_at[food.index()].saturating_sub(self.age)
+    }
+
+    pub fn feed(&mut self, food: Food) -> Result<Fed, Refusal> {
+        let stage = self.stage();
+        if stage == Stage::Dead {
+            return Err(Refusal::Dead);
+        }
+        if !stage.can_feed() {
+            return Err(Refusal::CantEat(stage));
+        }
+        if self.asleep {
+            return Err(Refusal::Asleep);
+        }
+        if self.misbehaving == Some(Misbehavior::Fussy) {
+            return Err(Refusal::Fussy);
+        }
+        let remaining = self.cooldown_remaining(food);
+        if !remaining.is_zero() {
+            return Err(Refusal::Cooldown { food, remaining });
+        }
+
+        let overfed = self.hunger <= FULL_THRESHOLD;
+        let mut effects = vec![personality::food_effect(food, &self.traits)];
+        if overfed {
+            effects.push(OVERFEED_PENALTY);
+        }
+        for effect in effects {
+            self.hunger = food::adjust(self.hunger, effect.hunger, 100);
+            self.happiness = food::adjust(self.happiness, effect.happiness, 100);
+            self.weight = food::adjust(self.weight, effect.weight, 999).max(1);
+            self.health = food::adjust(self.health, effect.health, 100);
+        }
+        self.food_ready_at[food.index()] = self.age + food.cooldown();
+        self.mess_due.get_or_insert(self.age + DIGESTION_TIME);
+        match food {
+            Food::Meal => self.care.meals += 1,
+            Food::Treat => self.care.treats += 1,
+            Food::Snack => {}
+        }
+        if overfed {
+            self.care.overfeeds += 1;
+        }
+        Ok(Fed { food, overfed })
+    }
+
+    /// Checks whether the pet is up for a game of `kind`.
+    pub fn can_play(&self, kind: GameKind) -> Result<(), Refusal> {
+        let stage = self.stage();
+        if stage == Stage::Dead {
+            return Err(Refusal::Dead);
+        }
+        if !stage.can_play(kind) {
+            return Err(Refusal::CantPlay(stage, kind));
+        }
+        if self.asleep {
+            return Err(Refusal::Asleep);
+        }
+        if let Some(illness) = self.illness {
+            return Err(Refusal::Sick(illness));
+        }
+        if self.energy < PLAY_ENERGY_COST {
+            return Err(Refusal::TooTired);
+        }
+        Ok(())
+    }
+
+    /// Applies the cost and reward of a finished game.
+    pub(crate) fn played(&mut self, outcome: Outcome) {
+        self.energy = self.energy.saturating_sub(PLAY_ENERGY_COST);
+        let gain = if outcome.won {
+            WIN_HAPPINESS
+        } else {
+            LOSE_HAPPINESS
+        };
+        self.happiness = (self.happiness + gain).min(100);
+    }
+
+    /// Cures the pet's illness. Medicine tastes bad, so it costs some happiness.
+    pub fn give_medicine(&mut self) -> Result<Illness, Refusal> {
+        if self.is_dead() {
+            return Err(Refusal::Dead);
+        }
+        let illness = self.illness.take().ok_or(Refusal::NotSick)?;
+        self.happiness = self.happiness.saturating_sub(MEDICINE_PENALTY);
+        Ok(illness)
+    }
+
+    /// Clears away all the waste and gives the pet a wash. Returns how many piles
+    /// were removed.
+    pub fn clean(&mut self) -> Result<usize, Refusal> {
+        if self.is_dead() {
+            return Err(Refusal::Dead);
+        }
+        if self.waste.is_empty() && self.cleanliness == 100 {
+            return Err(Refusal::AlreadyClean);
+        }
+        let piles = self.waste.len();
+        self.waste.clear();
+        self.cleanliness = 100;
+        Ok(piles)
+    }
+
+    /// Leaves a pile of waste at `spot`.
+    pub(crate) fn make_mess(&mut self, spot: (f64, f64)) {
+        if self.waste.len() < MAX_WASTE {
+            self.waste.push(spot);
+        }
+        self.cleanliness = self.cleanliness.saturating_sub(MESS_PENALTY);
+    }
+
+    /// Puts the pet to bed.
+    pub fn sleep(&mut self) -> Result<(), Refusal> {
+        let stage = self.stage();
+        if stage == Stage::Dead {
+            return Err(Refusal::Dead);
+        }
+        if !stage.can_move() {
+            return Err(Refusal::CantSleep(stage));
+        }
+        if self.asleep {
+            return Err(Refusal::Asleep);
+        }
+        self.asleep = true;
+        Ok(())
+    }
+
+    /// Wakes the pet up, which it doesn't appreciate.
+    pub fn wake(&mut self) -> Result<(), Refusal> {
+        if !self.asleep {
+            return Err(Refusal::Awake);
+        }
+        self.asleep = false;
+        let penalty = if self.traits.contains(&Trait::Grumpy) {
+            WAKE_PENALTY * 2
+        } else {
+            WAKE_PENALTY
+        };
+        self.happiness = self.happiness.saturating_sub(penalty);
+        Ok(())
+    }
+
+    /// Scolds or praises the pet, which only shapes its discipline when it's
+    /// deserved.
+    pub fn respond(&mut self, response: Response) -> Result<Reaction, Refusal> {
+        let stage = self.stage();
+        if stage == Stage::Dead {
+            return Err(Refusal::Dead);
+        }
+        if !stage.can_feed() {
+            return Err(Refusal::CantTrain(stage));
+        }
+        let reaction = match (response, self.misbehaving.is_some()) {
+            (Response::Scold, true) => {
+                self.misbehaving = None;
+                self.discipline = (self.discipline + DISCIPLINE_STEP).min(100);
+                self.happiness = self.happiness.saturating_sub(SCOLD_PENALTY);
+                Reaction::Learned
+            }
+            (Response::Scold, false) => {
+                self.happiness = self.happiness.saturating_sub(SCOLD_PENALTY * 2);
+                Reaction::Hurt
+            }
+            (Response::Praise, true) => {
+                self.discipline = self.discipline.saturating_sub(DISCIPLINE_STEP);
+                Reaction::Spoiled
+            }
+            (Response::Praise, false) if self.age < self.praise_ready_at => Reaction::Shrugged,
+            (Response::Praise, false) => {
+                self.happiness = (self.happiness + PRAISE_REWARD).min(100);
+                self.praise_ready_at = self.age + PRAISE_COOLDOWN;
+                Reaction::Pleased
+            }
+        };
+        Ok(reaction)
+    }
+
+    /// Pets the pet. Returns whether it enjoyed it, which it only does once in a
+    /// while.
+    pub fn stroke(&mut self) -> Result<bool, Refusal> {
+        let stage = self.stage();
+        if stage == Stage::Dead {
+            return Err(Refusal::Dead);
+        }
+        if stage == Stage::Egg {
+            return Err(Refusal::CantStroke(stage));
+        }
+        if self.asleep {
+            return Err(Refusal::Asleep);
+        }
+        if self.age < self.stroke_ready_at {
+            return Ok(false);
+        }
+        self.happiness = (self.happiness + STROKE_REWARD).min(100);
+        self.stroke_ready_at = self.age + STROKE_COOLDOWN;
+        Ok(true)
+    }
+
+    /// Moves the pet by `(dx, dy)`, keeping it inside `bounds`.
+    pub fn move_by(&mut self, dx: f64, dy: f64, bounds: Bounds) {
+        if !self.stage().can_move() || self.asleep {
+            return;
+        }
+        self.position = self.clamped(self.position.0 + dx, self.position.1 + dy, bounds);
+    }
+
+    /// The point closest to `(x, y)` where the pet fits inside `bounds`.
+    pub(crate) fn clamped(&self, x: f64, y: f64, bounds: Bounds) -> (f64, f64) {
+        (
+            x.max(bounds.left).min(bounds.right - PET_MARGIN),
+            y.max(bounds.bottom).min(bounds.top - PET_MARGIN),
+        )
+    }
+
+    /// Advances the pet by a single slice of simulated time.
+    ///
+    /// `dt` should be at most [`crate::clock::MAX_STEP`]; [`crate::World::step`] takes
+    /// care of splitting longer periods. Moving around is left to [`crate::behavior`].
+    pub(crate) fn step<R: Rng + ?Sized>(&mut self, dt: Duration, rng: &mut R) -> Vec<Change> {
+        self.live(dt, rng, false)
+    }
+
+    /// Like [`Pet::step`], but for time that passed while the game was closed.
+    ///
+    /// The pet still grows up, but the owner can't answer calls they never saw, so
+    /// hunger and happiness stop short of calling and the pet doesn't fall ill.
+    /// Needs that were already pressing when the game closed are left as they were.
+    pub(crate) fn step_away<R: Rng + ?Sized>(&mut self, dt: Duration, rng: &mut R) -> Vec<Change> {
+        self.live(dt, rng, true)
+    }
+
+    fn live<R: Rng + ?Sized>(&mut self, dt: Duration, rng: &mut R, away: bool) -> Vec<Change> {
+        let stage = self.stage();
+        if stage == Stage::Dead {
+            return Vec::new();
+        }
+        self.tick_count += 1;
+        self.age += dt;
+
+        let new_stage = Stage::for_age(self.age);
+        if new_stage == Stage::Dead {
+            return vec![self.die(DeathCause::OldAge)];
+        }
+        let mut changes = Vec::new();
+        if stage == Stage::Egg && new_stage != stage {
+            self.traits = personality::pick(rng);
+        }
+        if new_stage != stage {
+            changes.push(Change::Grew {
+                from: stage,
+                to: new_stage,
+            });
+            // How the last stage went decides what the pet grows into
+            let form = match self.form {
+                None => Form::HATCHLING,
+                Some(form) if new_stage == form.stage() => form,
+                Some(form) => form.next(&self.care),
+            };
+            if self.form != Some(form) {
+                self.form = Some(form);
+                changes.push(Change::Evolved(form));
+            }
+            self.care = CareRecord::default();
+        }
+
+        // Needs build up more slowly while asleep
+        let decay = if self.asleep { dt / SLEEP_SLOWDOWN } else { dt };
+
+        // Increase hunger over time, faster for young and old pets
+        if let Some(interval) = new_stage.hunger_interval() {
+            let interval = personality::hunger_interval(interval, &self.traits);
+            let points = self.hunger_timer.tick(decay, interval);
+            let most = if away {
+                self.hunger.max(CALL_HUNGER - 1)
+            } else {
+                100
+            };
+            self.hunger = (self.hunger + points).min(most);
+        }
+
+        // Decrease happiness over time
+        if let Some(interval) = new_stage.happiness_interval() {
+            let interval = personality::happiness_interval(interval, &self.traits);
+            let points = self.happiness_timer.tick(decay, interval);
+            let least = if away {
+                self.happiness.min(CALL_HAPPINESS + 1)
+            } else {
+                0
+            };
+            self.happiness = self.happiness.saturating_sub(points).max(least);
+        }
+
+        // Call for the owner when a need gets pressing, and remember if nobody came
+        match self.call {
+            Some(call) if !call.need.is_pressing(self) => self.call = None,
+            Some(call)
+                if call.is_open() && self.age >= call.since + care::call_timeout(new_stage) =>
+            {
+                self.call = Some(Call {
+                    ignored: true,
+                    ..call
+                });
+                self.care_mistakes += 1;
+                self.care.mistakes += 1;
+                // Neglected pets grow up unruly
+                self.discipline = self.discipline.saturating_sub(DISCIPLINE_STEP);
+                changes.push(Change::CallIgnored(call.need));
+            }
+            Some(_) => {}
+            None => {
+                if let Some(need) = care::pressing_need(self) {
+                    self.call = Some(Call {
+                        need,
+                        since: self.age,
+                        ignored: false,
+                    });
+                    changes.push(Change::Called(need));
+                }
+            }
+        }
+
+        // Keep track of how well the pet is looked after in this stage
+        self.care.time += dt;
+        if evolution::in_danger(self) {
+            self.care.danger += dt;
+        }
+
+        // A pet left starving for too long dies of neglect
+        if self.hunger >= 100 {
+            self.starving_for += dt;
+            if self.starving_for >= NEGLECT_LIMIT {
+                changes.push(self.die(DeathCause::Neglect));
+                return changes;
+            }
+        } else {
+            self.starving_for = Duration::ZERO;
+        }
+
+        // Food comes out some time after going in, and any mess left lying around
+        // makes the pet dirtier
+        if self.mess_due.is_some_and(|due| self.age >= due) {
+            self.mess_due = None;
+            changes.push(Change::MadeAMess);
+        }
+        let points = self.dirt_timer.tick(dt, DIRT_INTERVAL) * self.waste.len() as u32;
+        self.cleanliness = self.cleanliness.saturating_sub(points);
+
+        // Untreated illnesses wear down health, otherwise it slowly recovers
+        if let Some(illness) = self.illness {
+            let points = self.health_timer.tick(dt, illness.drain_interval());
+            self.health = self.health.saturating_sub(points);
+            if self.health == 0 {
+                changes.push(self.die(DeathCause::Illness(illness)));
+                return changes;
+            }
+        } else {
+            let points = self.health_timer.tick(dt, health::RECOVERY_INTERVAL);
+            if self.hunger < 100 {
+                self.health = (self.health + points).min(100);
+            }
+            let checks = self.checkup_timer.tick(dt, health::CHECK_INTERVAL);
+            // Nobody is around to give medicine while the game is closed
+            let checks = if away { 0 } else { checks };
+            for _ in 0..checks {
+                if let Some(illness) = health::roll(self, rng) {
+                    self.illness = Some(illness);
+                    self.health_timer = Every::default();
+                    changes.push(Change::FellIll(illness));
+                    break;
+                }
+            }
+        }
+
+        // Undisciplined pets act up every now and then
+        if self.misbehaving.is_some() && self.age >= self.mischief_ends_at {
+            self.misbehaving = None;
+        }
+        let checks = self.mischief_timer.tick(dt, MISCHIEF_INTERVAL);
+        if checks > 0 && self.misbehaving.is_none() && !self.asleep && new_stage.can_feed() {
+            let chance = personality::mischief_chance(self.discipline, &self.traits);
+            if rng.random_range(0..100) < chance {
+                let misbehavior = Misbehavior::pick(&self.traits, rng);
+                self.misbehaving = Some(misbehavior);
+                self.mischief_ends_at = self.age + MISCHIEF_TIMEOUT;
+                changes.push(Change::Misbehaved(misbehavior));
+            }
+        }
+
+        // Tire while awake and recover while asleep, waking up once fully rested
+        if self.asleep {
+            let points = self.energy_timer.tick(dt, RESTING_INTERVAL);
+            self.energy = (self.energy + points).min(100);
+            if self.energy == 100 {
+                self.asleep = false;
+                changes.push(Change::WokeUp);
+            }
+        } else if new_stage.can_move() {
+            let points = self.energy_timer.tick(dt, TIRING_INTERVAL);
+            self.energy = self.energy.saturating_sub(points);
+            if self.energy <= DROWSY_AT {
+                self.asleep = true;
+                changes.push(Change::FellAsleep);
+            }
+        }
+        changes
+    }
+
+    fn die(&mut self, cause: DeathCause) -> Change {
+        self.cause_of_death = Some(cause);
+        self.asleep = false;
+        Change::Died(cause)
+    }
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Dead => write!(f, "Your pet has passed away"),
+            Refusal::CantEat(stage) => write!(f, "{} can't eat yet", stage.name()),
+            Refusal::CantPlay(stage, kind) => {
+                write!(f, "{} can't play {}", stage.name(), kind.name())
+            }
+            Refusal::Cooldown { food, remaining } => write!(
+                f,
+                "Not hungry for a {} yet ({}s)",
+                food.name(),
+                remaining.as_secs() + 1
+            ),
+            Refusal::TooTired => write!(f, "Too tired to play"),
+            Refusal::Asleep => write!(f, "Shh, your pet is sleeping"),
+            Refusal::CantSleep(stage) => write!(f, "{} can't be put to bed", stage.name()),
+            Refusal::Awake => write!(f, "Your pet is already awake"),
+            Refusal::Sick(illness) => write!(f, "Too sick to play ({})", illness.name()),
+            Refusal::NotSick => write!(f, "Your pet isn't sick"),
+            Refusal::AlreadyClean => write!(f, "Everything is already spotless"),
+            Refusal::Fussy => write!(f, "Your pet turns its nose up at the food"),
+            Refusal::CantTrain(stage) => write!(f, "{} is too young to understand", stage.name()),
+            Refusal::CantStroke(stage) => {
+                write!(f, "The {} doesn't notice", stage.name().to_lowercase())
+            }
+            Refusal::NoneLeft(product) => {
+                write!(
+                    f,
+                    "Out of {}s, buy more in the shop",
+                    product.name().to_lowercase()
+                )
+            }
+            Refusal::CantAfford(product) => write!(
+                f,
+                "Not enough coins for a {} ({})",
+                product.name().to_lowercase(),
+                product.price()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::*;
+    use crate::{care::CALL_TIMEOUT, world::PLAYGROUND};
+
+    /// Always rolls the highest value, so the pet never falls ill.
+    struct Lucky;
+
+    impl RngCore for Lucky {
+        fn next_u32(&mut self) -> u32 {
+            u32::MAX
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            u64::MAX
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            dst.fill(u8::MAX);
+        }
+    }
+
+    /// A pet old enough to eat and play.
+    fn child() -> Pet {
+        let mut pet = Pet::new();
+        pet.age = Duration::from_secs(20 * 60);
+        pet
+    }
+
+    #[test]
+    fn eggs_cannot_eat() {
+        let mut pet = Pet::new();
+        assert_eq!(pet.feed(Food::Meal), Err(Refusal::CantEat(Stage::Egg)));
+    }
+
+    #[test]
+    fn feeding_lowers_hunger() {
+        let mut pet = child();
+        pet.hunger = 60;
+        let fed = pet.feed(Food::Meal).unwrap();
+        assert!(!fed.overfed);
+        assert_eq!(pet.hunger, 20);
+        assert_eq!(pet.weight, 7);
+    }
+
+    #[test]
+    fn feeding_a_full_pet_is_penalised() {
+        let mut pet = child();
+        pet.hunger = 0;
+        pet.happiness = 50;
+        let fed = pet.feed(Food::Snack).unwrap();
+        assert!(fed.overfed);
+        assert_eq!(pet.happiness, 50);
+        assert_eq!(pet.health, 90);
+    }
+
+    #[test]
+    fn food_has_a_cooldown() {
+        let mut pet = child();
+        pet.hunger = 80;
+        pet.feed(Food::Snack).unwrap();
+        assert!(matches!(
+            pet.feed(Food::Snack),
+            Err(Refusal::Cooldown {
+                food: Food::Snack,
+                ..
+            })
+        ));
+        pet.age += Food::Snack.cooldown();
+        assert!(pet.feed(Food::Snack).is_ok());
+    }
+
+    #[test]
+    fn egg_hatches_after_a_minute() {
+        let mut pet = Pet::new();
+        let changes = pet.step(Duration::from_secs(60), &mut Lucky);
+        assert_eq!(
+            changes[0],
+            Change::Grew {
+                from: Stage::Egg,
+                to: Stage::Baby
+            }
+        );
+    }
+
+    #[test]
+    fn care_decides_what_the_pet_grows_into() {
+        let mut pet = Pet::new();
+        assert!(pet
+            .step(Duration::from_secs(60), &mut Lucky)
+            .contains(&Change::Evolved(Form::Babblet)));
+        assert_eq!(pet.form, Some(Form::Babblet));
+
+        pet.age = Duration::from_secs(10 * 60 - 1);
+        pet.care.mistakes = 1;
+        assert!(pet
+            .step(Duration::from_secs(1), &mut Lucky)
+            .contains(&Change::Evolved(Form::Scamp)));
+        assert_eq!(pet.form, Some(Form::Scamp));
+        assert_eq!(pet.care.mistakes, 0);
+    }
+
+    #[test]
+    fn starving_pet_dies_of_neglect() {
+        let mut pet = child();
+        pet.hunger = 100;
+        let mut changes = Vec::new();
+        for _ in 0..NEGLECT_LIMIT.as_secs() {
+            changes.extend(pet.step(Duration::from_secs(1), &mut Lucky));
+        }
+        assert_eq!(changes.last(), Some(&Change::Died(DeathCause::Neglect)));
+        assert_eq!(pet.stage(), Stage::Dead);
+    }
+
+    #[test]
+    fn tired_pet_falls_asleep_and_wakes_rested() {
+        let mut pet = child();
+        pet.energy = DROWSY_AT + 1;
+        assert_eq!(pet.step(TIRING_INTERVAL, &mut Lucky), [Change::FellAsleep]);
+        assert_eq!(pet.feed(Food::Meal), Err(Refusal::Asleep));
+        let mut changes = Vec::new();
+        for _ in 0..RESTING_INTERVAL.as_secs() * 90 {
+            changes.extend(pet.step(Duration::from_secs(1), &mut Lucky));
+        }
+        assert!(changes.contains(&Change::WokeUp));
+        assert!(!pet.asleep);
+        assert_eq!(pet.energy, 100);
+    }
+
+    #[test]
+    fn needs_decay_slower_while_asleep() {
+        let mut awake = child();
+        let mut asleep = child();
+        asleep.energy = 50;
+        asleep.sleep().unwrap();
+        for _ in 0..20 {
+            awake.step(Duration::from_secs(3), &mut Lucky);
+            asleep.step(Duration::from_secs(3), &mut Lucky);
+        }
+        assert_eq!(awake.hunger, 75);
+        assert_eq!(asleep.hunger, 25);
+    }
+
+    #[test]
+    fn waking_costs_happiness() {
+        let mut pet = child();
+        assert_eq!(pet.wake(), Err(Refusal::Awake));
+        pet.sleep().unwrap();
+        pet.wake().unwrap();
+        assert_eq!(pet.happiness, 100 - WAKE_PENALTY);
+    }
+
+    #[test]
+    fn untreated_illness_is_fatal() {
+        let mut pet = child();
+        pet.illness = Some(Illness::Fever);
+        assert_eq!(
+            pet.can_play(GameKind::Guess),
+            Err(Refusal::Sick(Illness::Fever))
+        );
+        let mut changes = Vec::new();
+        for _ in 0..Illness::Fever.drain_interval().as_secs() * 100 {
+            changes.extend(pet.step(Duration::from_secs(1), &mut Lucky));
+        }
+        assert_eq!(
+            changes.last(),
+            Some(&Change::Died(DeathCause::Illness(Illness::Fever)))
+        );
+    }
+
+    #[test]
+    fn medicine_cures_illness() {
+        let mut pet = child();
+        assert_eq!(pet.give_medicine(), Err(Refusal::NotSick));
+        pet.illness = Some(Illness::Cold);
+        assert_eq!(pet.give_medicine(), Ok(Illness::Cold));
+        assert_eq!(pet.illness, None);
+        assert_eq!(pet.happiness, 100 - MEDICINE_PENALTY);
+    }
+
+    #[test]
+    fn eating_leads_to_a_mess() {
+        let mut pet = child();
+        pet.hunger = 60;
+        pet.feed(Food::Meal).unwrap();
+        let mut changes = Vec::new();
+        for _ in 0..DIGESTION_TIME.as_secs() {
+            changes.extend(pet.step(Duration::from_secs(1), &mut Lucky));
+        }
+        assert!(changes.contains(&Change::MadeAMess));
+
+        pet.make_mess((50.0, 20.0));
+        for _ in 0..DIRT_INTERVAL.as_secs() {
+            pet.step(Duration::from_secs(1), &mut Lucky);
+        }
+        assert_eq!(pet.cleanliness, 100 - MESS_PENALTY - 1);
+        assert_eq!(pet.clean(), Ok(1));
+        assert_eq!(pet.cleanliness, 100);
+        assert_eq!(pet.clean(), Err(Refusal::AlreadyClean));
+    }
+
+    #[test]
+    fn scolding_only_helps_when_deserved() {
+        let mut pet = child();
+        assert_eq!(pet.respond(Response::Scold), Ok(Reaction::Hurt));
+        assert_eq!(pet.discipline, 50);
+        assert_eq!(pet.happiness, 100 - SCOLD_PENALTY * 2);
+
+        pet.misbehaving = Some(Misbehavior::Fussy);
+        assert_eq!(pet.feed(Food::Snack), Err(Refusal::Fussy));
+        assert_eq!(pet.respond(Response::Scold), Ok(Reaction::Learned));
+        assert_eq!(pet.discipline, 50 + DISCIPLINE_STEP);
+        assert!(pet.feed(Food::Snack).is_ok());
+    }
+
+    #[test]
+    fn praise_has_a_cooldown() {
+        let mut pet = child();
+        pet.happiness = 50;
+        assert_eq!(pet.respond(Response::Praise), Ok(Reaction::Pleased));
+        assert_eq!(pet.respond(Response::Praise), Ok(Reaction::Shrugged));
+        assert_eq!(pet.happiness, 50 + PRAISE_REWARD);
+        pet.misbehaving = Some(Misbehavior::FakeCall);
+        assert_eq!(pet.respond(Response::Praise), Ok(Reaction::Spoiled));
+        assert_eq!(pet.discipline, 50 - DISCIPLINE_STEP);
+    }
+
+    #[test]
+    fn petting_has_a_cooldown() {
+        assert_eq!(Pet::new().stroke(), Err(Refusal::CantStroke(Stage::Egg)));
+        let mut pet = child();
+        pet.happiness = 50;
+        assert_eq!(pet.stroke(), Ok(true));
+        assert_eq!(pet.stroke(), Ok(false));
+        assert_eq!(pet.happiness, 50 + STROKE_REWARD);
+        pet.age += STROKE_COOLDOWN;
+        assert_eq!(pet.stroke(), Ok(true));
+    }
+
+    #[test]
+    fn unanswered_calls_are_care_mistakes() {
+        let mut pet = child();
+        pet.hunger = 99;
+        assert_eq!(
+            pet.step(Duration::from_secs(1), &mut Lucky),
+            [Change::Called(Need::Food)]
+        );
+        let mut changes = Vec::new();
+        for _ in 0..CALL_TIMEOUT.as_secs() {
+            changes.extend(pet.step(Duration::from_secs(1), &mut Lucky));
+        }
+        assert!(changes.contains(&Change::CallIgnored(Need::Food)));
+        assert_eq!(pet.care_mistakes, 1);
+
+        pet.feed(Food::Meal).unwrap();
+        pet.feed(Food::Snack).unwrap();
+        pet.step(Duration::from_secs(1), &mut Lucky);
+        assert_eq!(pet.call, None);
+        assert_eq!(pet.care_mistakes, 1);
+    }
+
+    #[test]
+    fn pet_stays_inside_the_playground() {
+        let mut pet = child();
+        for _ in 0..100 {
+            pet.move_by(-100.0, 100.0, PLAYGROUND);
+        }
+        assert_eq!(pet.position, (PLAYGROUND.left, PLAYGROUND.top - PET_MARGIN));
+    }
+}
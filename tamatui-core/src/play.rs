@@ -0,0 +1,288 @@
+//! Mini-games the owner can play with the pet.
+//!
+//! While a game is running the pet's needs are paused; the game is updated with
+//! real elapsed time instead.
+
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::world::PLAYGROUND;
+
+/// Energy spent by finishing any game.
+pub const PLAY_ENERGY_COST: u32 = 15;
+/// Happiness gained by winning a game.
+pub const WIN_HAPPINESS: u32 = 20;
+/// Happiness gained by playing a game, even when losing.
+pub const LOSE_HAPPINESS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameKind {
+    Guess,
+    Catch,
+}
+
+impl GameKind {
+    pub const ALL: [GameKind; 2] = [GameKind::Guess, GameKind::Catch];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameKind::Guess => "Left or Right?",
+            GameKind::Catch => "Catch the treats",
+        }
+    }
+
+    pub fn start<R: Rng + ?Sized>(self, rng: &mut R) -> Game {
+        match self {
+            GameKind::Guess => Game::Guess(GuessGame::new(rng)),
+            GameKind::Catch => Game::Catch(CatchGame::new()),
+        }
+    }
+}
+
+/// Player input for a mini-game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// The result of a finished game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    pub won: bool,
+    pub score: u32,
+    pub out_of: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Game {
+    Guess(GuessGame),
+    Catch(CatchGame),
+}
+
+impl Game {
+    pub fn kind(&self) -> GameKind {
+        match self {
+            Game::Guess(_) => GameKind::Guess,
+            Game::Catch(_) => GameKind::Catch,
+        }
+    }
+
+    pub fn update<R: Rng + ?Sized>(&mut self, dt: Duration, rng: &mut R) {
+        match self {
+            Game::Guess(_) => {}
+            Game::Catch(game) => game.update(dt, rng),
+        }
+    }
+
+    pub fn input<R: Rng + ?Sized>(&mut self, side: Side, rng: &mut R) {
+        match self {
+            Game::Guess(game) => game.guess(side, rng),
+            Game::Catch(game) => game.move_basket(side),
+        }
+    }
+
+    /// `Some` once the game is over.
+    pub fn outcome(&self) -> Option<Outcome> {
+        match self {
+            Game::Guess(game) => game.outcome(),
+            Game::Catch(game) => game.outcome(),
+        }
+    }
+}
+
+/// The pet looks to one side and the owner guesses which one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuessGame {
+    round: u32,
+    correct: u32,
+    secret: Side,
+    last: Option<(Side, bool)>,
+}
+
+impl GuessGame {
+    pub const ROUNDS: u32 = 5;
+    pub const TO_WIN: u32 = 3;
+
+    fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            round: 0,
+            correct: 0,
+            secret: random_side(rng),
+            last: None,
+        }
+    }
+
+    /// Number of rounds already played.
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    pub fn correct(&self) -> u32 {
+        self.correct
+    }
+
+    /// Where the pet looked last round and whether the guess was right.
+    pub fn last(&self) -> Option<(Side, bool)> {
+        self.last
+    }
+
+    fn guess<R: Rng + ?Sized>(&mut self, guess: Side, rng: &mut R) {
+        if self.round >= Self::ROUNDS {
+            return;
+        }
+        let right = guess == self.secret;
+        if right {
+            self.correct += 1;
+        }
+        self.last = Some((self.secret, right));
+        self.round += 1;
+        self.secret = random_side(rng);
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        (self.round >= Self::ROUNDS).then_some(Outcome {
+            won: self.correct >= Self::TO_WIN,
+            score: self.correct,
+            out_of: Self::ROUNDS,
+        })
+    }
+}
+
+fn random_side<R: Rng + ?Sized>(rng: &mut R) -> Side {
+    if rng.random_bool(0.5) {
+        Side::Left
+    } else {
+        Side::Right
+    }
+}
+
+/// Treats fall from the top of the playground; move the basket to catch them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatchGame {
+    basket_x: f64,
+    treats: Vec<(f64, f64)>,
+    spawned: u32,
+    caught: u32,
+    spawn_timer: Duration,
+}
+
+impl CatchGame {
+    pub const TREATS: u32 = 15;
+    pub const TO_WIN: u32 = 10;
+    pub const BASKET_Y: f64 = PLAYGROUND.bottom + 5.0;
+    pub const BASKET_HALF_WIDTH: f64 = 12.0;
+    const SPAWN_EVERY: Duration = Duration::from_millis(750);
+    /// Distance a treat falls per second.
+    const FALL_SPEED: f64 = 60.0;
+    const BASKET_STEP: f64 = 8.0;
+
+    fn new() -> Self {
+        Self {
+            basket_x: PLAYGROUND.center().0,
+            treats: Vec::new(),
+            spawned: 0,
+            caught: 0,
+            spawn_timer: Duration::ZERO,
+        }
+    }
+
+    pub fn basket_x(&self) -> f64 {
+        self.basket_x
+    }
+
+    /// Positions of the treats still falling.
+    pub fn treats(&self) -> &[(f64, f64)] {
+        &self.treats
+    }
+
+    pub fn caught(&self) -> u32 {
+        self.caught
+    }
+
+    fn update<R: Rng + ?Sized>(&mut self, dt: Duration, rng: &mut R) {
+        self.spawn_timer += dt;
+        if self.spawned < Self::TREATS && self.spawn_timer >= Self::SPAWN_EVERY {
+            self.spawn_timer -= Self::SPAWN_EVERY;
+            let x = rng.random_range(PLAYGROUND.left + 5.0..PLAYGROUND.right - 5.0);
+            self.treats.push((x, PLAYGROUND.top));
+            self.spawned += 1;
+        }
+
+        for treat in &mut self.treats {
+            treat.1 -= Self::FALL_SPEED * dt.as_secs_f64();
+        }
+        let basket_x = self.basket_x;
+        let before = self.treats.len();
+        self.treats.retain(|&(x, y)| {
+            !(y <= Self::BASKET_Y + 2.0 && (x - basket_x).abs() <= Self::BASKET_HALF_WIDTH)
+        });
+        self.caught += (before - self.treats.len()) as u32;
+        // Anything that reaches the ground is lost
+        self.treats.retain(|&(_, y)| y > PLAYGROUND.bottom);
+    }
+
+    fn move_basket(&mut self, side: Side) {
+        match side {
+            Side::Left => self.basket_x -= Self::BASKET_STEP,
+            Side::Right => self.basket_x += Self::BASKET_STEP,
+        }
+        self.basket_x = self.basket_x.clamp(
+            PLAYGROUND.left + Self::BASKET_HALF_WIDTH,
+            PLAYGROUND.right - Self::BASKET_HALF_WIDTH,
+        );
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        (self.spawned == Self::TREATS && self.treats.is_empty()).then_some(Outcome {
+            won: self.caught >= Self::TO_WIN,
+            score: self.caught,
+            out_of: Self::TREATS,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn guess_game_ends_after_all_rounds() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut game = GameKind::Guess.start(&mut rng);
+        for _ in 0..GuessGame::ROUNDS {
+            assert_eq!(game.outcome(), None);
+            game.input(Side::Left, &mut rng);
+        }
+        let outcome = game.outcome().unwrap();
+        assert_eq!(outcome.out_of, GuessGame::ROUNDS);
+        assert!(outcome.score <= GuessGame::ROUNDS);
+    }
+
+    #[test]
+    fn catch_game_ends_once_every_treat_has_landed() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut game = GameKind::Catch.start(&mut rng);
+        for _ in 0..1000 {
+            game.update(Duration::from_millis(100), &mut rng);
+        }
+        let outcome = game.outcome().unwrap();
+        assert_eq!(outcome.out_of, CatchGame::TREATS);
+    }
+
+    #[test]
+    fn basket_stays_in_the_playground() {
+        let mut game = CatchGame::new();
+        for _ in 0..100 {
+            game.move_basket(Side::Left);
+        }
+        assert_eq!(
+            game.basket_x(),
+            PLAYGROUND.left + CatchGame::BASKET_HALF_WIDTH
+        );
+    }
+}
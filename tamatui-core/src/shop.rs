@@ -0,0 +1,134 @@
+//! Coins, the things they buy and what the owner has in stock.
+//!
+//! Meals and snacks are free, but treats and medicine come out of the owner's
+//! [`Inventory`]. Coins are earned by winning mini-games. Sick pets can't play,
+//! so an owner with no medicine and too few coins to buy any is given a dose.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{pet::Refusal, play::Outcome};
+
+/// Coins a new owner starts out with.
+pub const STARTING_COINS: u32 = 20;
+/// Coins earned for each point scored in a game that was won.
+pub const COINS_PER_POINT: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Product {
+    Treat,
+    Medicine,
+}
+
+impl Product {
+    pub const ALL: [Product; 2] = [Product::Treat, Product::Medicine];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Product::Treat => "Treat",
+            Product::Medicine => "Medicine",
+        }
+    }
+
+    pub fn price(self) -> u32 {
+        match self {
+            Product::Treat => 8,
+            Product::Medicine => 15,
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Product::Treat => "A sweet that makes your pet very happy",
+            Product::Medicine => "Cures any illness, but tastes awful",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Product::Treat => 0,
+            Product::Medicine => 1,
+        }
+    }
+}
+
+/// The owner's coins and stock, kept across generations of pets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Inventory {
+    pub coins: u32,
+    /// How many of each product are left, indexed by [`Product::index`].
+    pub stock: [u32; Product::ALL.len()],
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            coins: STARTING_COINS,
+            stock: [3, 2],
+        }
+    }
+}
+
+impl Inventory {
+    pub fn count(&self, product: Product) -> u32 {
+        self.stock[product.index()]
+    }
+
+    /// Fails without using anything up if `product` is out of stock.
+    pub(crate) fn check(&self, product: Product) -> Result<(), Refusal> {
+        if self.count(product) == 0 {
+            return Err(Refusal::NoneLeft(product));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn take(&mut self, product: Product) {
+        let count = &mut self.stock[product.index()];
+        *count = count.saturating_sub(1);
+    }
+
+    pub fn buy(&mut self, product: Product) -> Result<(), Refusal> {
+        let price = product.price();
+        if self.coins < price {
+            return Err(Refusal::CantAfford(product));
+        }
+        self.coins -= price;
+        self.stock[product.index()] += 1;
+        Ok(())
+    }
+}
+
+/// Coins earned for a finished game.
+pub fn reward(outcome: Outcome) -> u32 {
+    if outcome.won {
+        outcome.score * COINS_PER_POINT
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buying_costs_coins() {
+        let mut inventory = Inventory {
+            coins: 10,
+            stock: [0, 0],
+        };
+        assert_eq!(
+            inventory.check(Product::Treat),
+            Err(Refusal::NoneLeft(Product::Treat))
+        );
+        assert!(inventory.buy(Product::Treat).is_ok());
+        assert_eq!(inventory.coins, 10 - Product::Treat.price());
+        assert_eq!(inventory.count(Product::Treat), 1);
+        assert_eq!(
+            inventory.buy(Product::Medicine),
+            Err(Refusal::CantAfford(Product::Medicine))
+        );
+        inventory.take(Product::Treat);
+        assert_eq!(inventory.count(Product::Treat), 0);
+    }
+}
@@ -0,0 +1,151 @@
+//! The pet's life cycle, from egg to elder.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{health::Illness, play::GameKind};
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// How long the pet survives with hunger pegged at 100 before dying of neglect.
+pub const NEGLECT_LIMIT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    Egg,
+    Baby,
+    Child,
+    Teen,
+    Adult,
+    Elder,
+    Dead,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeathCause {
+    OldAge,
+    Neglect,
+    Illness(Illness),
+}
+
+impl Stage {
+    /// The stage a living pet of the given age is in.
+    ///
+    /// Returns [`Stage::Dead`] once the pet has outlived the elder stage.
+    pub fn for_age(age: Duration) -> Self {
+        if age < MINUTE {
+            Stage::Egg
+        } else if age < 10 * MINUTE {
+            Stage::Baby
+        } else if age < HOUR {
+            Stage::Child
+        } else if age < 3 * HOUR {
+            Stage::Teen
+        } else if age < 48 * HOUR {
+            Stage::Adult
+        } else if age < 96 * HOUR {
+            Stage::Elder
+        } else {
+            Stage::Dead
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Egg => "Egg",
+            Stage::Baby => "Baby",
+            Stage::Child => "Child",
+            Stage::Teen => "Teen",
+            Stage::Adult => "Adult",
+            Stage::Elder => "Elder",
+            Stage::Dead => "Dead",
+        }
+    }
+
+    /// Simulated time between each point of hunger, or `None` if the pet doesn't get hungry.
+    pub fn hunger_interval(self) -> Option<Duration> {
+        let millis = match self {
+            Stage::Egg | Stage::Dead => return None,
+            // Babies need feeding often
+            Stage::Baby => 700,
+            Stage::Child => 800,
+            Stage::Teen => 1000,
+            Stage::Adult => 1200,
+            Stage::Elder => 800,
+        };
+        Some(Duration::from_millis(millis))
+    }
+
+    /// Simulated time between each point of lost happiness, or `None` if happiness doesn't decay.
+    pub fn happiness_interval(self) -> Option<Duration> {
+        let millis = match self {
+            Stage::Egg | Stage::Dead => return None,
+            Stage::Baby => 1500,
+            Stage::Child => 1800,
+            Stage::Teen => 2000,
+            Stage::Adult => 2300,
+            Stage::Elder => 1600,
+        };
+        Some(Duration::from_millis(millis))
+    }
+
+    pub fn can_move(self) -> bool {
+        !matches!(self, Stage::Egg | Stage::Dead)
+    }
+
+    /// Top walking speed in playground units per second of simulated time.
+    pub fn speed(self) -> f64 {
+        match self {
+            Stage::Egg | Stage::Dead => 0.0,
+            // Babies toddle and elders shuffle
+            Stage::Baby => 15.0,
+            Stage::Child => 30.0,
+            Stage::Teen => 40.0,
+            Stage::Adult => 30.0,
+            Stage::Elder => 12.0,
+        }
+    }
+
+    pub fn can_feed(self) -> bool {
+        !matches!(self, Stage::Egg | Stage::Dead)
+    }
+
+    pub fn can_play(self, kind: GameKind) -> bool {
+        match self {
+            Stage::Egg | Stage::Baby | Stage::Dead => false,
+            // Elders are too slow to chase falling treats
+            Stage::Elder => kind != GameKind::Catch,
+            Stage::Child | Stage::Teen | Stage::Adult => true,
+        }
+    }
+}
+
+impl DeathCause {
+    pub fn describe(self) -> &'static str {
+        match self {
+            DeathCause::OldAge => "Passed away peacefully of old age",
+            DeathCause::Neglect => "Starved from neglect",
+            DeathCause::Illness(Illness::Cold) => "Never recovered from a cold",
+            DeathCause::Illness(Illness::Tummyache) => "Succumbed to a tummyache",
+            DeathCause::Illness(Illness::Fever) => "Lost the fight with a fever",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_follow_age() {
+        assert_eq!(Stage::for_age(Duration::ZERO), Stage::Egg);
+        assert_eq!(Stage::for_age(MINUTE), Stage::Baby);
+        assert_eq!(Stage::for_age(HOUR - MINUTE), Stage::Child);
+        assert_eq!(Stage::for_age(2 * HOUR), Stage::Teen);
+        assert_eq!(Stage::for_age(24 * HOUR), Stage::Adult);
+        assert_eq!(Stage::for_age(72 * HOUR), Stage::Elder);
+        assert_eq!(Stage::for_age(96 * HOUR), Stage::Dead);
+    }
+}
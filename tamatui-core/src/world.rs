@@ -0,0 +1,599 @@
+//! The pet together with its playground and whatever game is being played.
+
+use std::{fmt, time::Duration};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    behavior::{self, Activity, Behavior, Item, ItemKind},
+    care::Need,
+    clock::MAX_STEP,
+    evolution::Form,
+    food::Food,
+    health::Illness,
+    personality::{Misbehavior, Reaction, Response},
+    pet::{Change, Fed, Pet, Refusal},
+    play::{Game, GameKind, Outcome, Side},
+    shop::{self, Inventory, Product},
+    stage::{DeathCause, Stage},
+};
+
+/// An axis-aligned area in playground coordinates, with `y` pointing up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub left: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub top: f64,
+}
+
+impl Bounds {
+    pub fn width(&self) -> f64 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f64 {
+        self.top - self.bottom
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (
+            (self.left + self.right) / 2.0,
+            (self.bottom + self.top) / 2.0,
+        )
+    }
+
+    /// A playground as tall as [`PLAYGROUND`] and `aspect` times as wide as it is
+    /// tall, within [`MIN_ASPECT`] and [`MAX_ASPECT`].
+    pub fn fitting(aspect: f64) -> Bounds {
+        let aspect = aspect.clamp(MIN_ASPECT, MAX_ASPECT);
+        Bounds {
+            right: PLAYGROUND.left + PLAYGROUND.height() * aspect,
+            ..PLAYGROUND
+        }
+    }
+
+    /// The point in `to` at the same relative spot as `point` in `self`.
+    pub fn map_to(&self, to: Bounds, point: (f64, f64)) -> (f64, f64) {
+        (
+            to.left + (point.0 - self.left) / self.width() * to.width(),
+            to.bottom + (point.1 - self.bottom) / self.height() * to.height(),
+        )
+    }
+}
+
+/// The playground every pet starts in.
+pub const PLAYGROUND: Bounds = Bounds {
+    left: 10.0,
+    right: 210.0,
+    bottom: 10.0,
+    top: 110.0,
+};
+
+/// The world's source of randomness. Its whole state can be saved, so a run can
+/// be picked up again exactly where it left off.
+pub type SimRng = ChaCha8Rng;
+
+/// Narrowest and widest shapes a fitted playground can take, as width over height.
+pub const MIN_ASPECT: f64 = 0.75;
+pub const MAX_ASPECT: f64 = 4.0;
+
+/// How close the pet has to get to food on the ground to eat it.
+pub const REACH: f64 = 8.0;
+
+/// Something worth telling the owner about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Hatched,
+    Grew(Stage),
+    /// Took a new branch of the evolution tree.
+    Evolved(Form),
+    Died(DeathCause),
+    FellAsleep,
+    WokeUp,
+    FellIll(Illness),
+    MadeAMess,
+    Misbehaved(Misbehavior),
+    Called(Need),
+    CallIgnored(Need),
+    /// Ate food that was dropped on the ground.
+    Ate(Fed),
+    GameOver {
+        kind: GameKind,
+        outcome: Outcome,
+    },
+}
+
+/// Everything the simulation needs to carry on exactly where it left off.
+///
+/// The pet's fields are serialized alongside the rest rather than nested, which
+/// is how saves have always stored them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct World {
+    #[serde(flatten)]
+    pub pet: Pet,
+    pub playground: Bounds,
+    /// Things in the playground the pet can go after.
+    pub items: Vec<Item>,
+    /// The owner's coins and supplies.
+    pub inventory: Inventory,
+    /// Seed the world's randomness started from. The same seed and the same
+    /// inputs always lead to the same world.
+    pub seed: u64,
+    /// Where every random choice in the world comes from.
+    pub rng: SimRng,
+    behavior: Behavior,
+    game: Option<Game>,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new(Pet::new(), 0)
+    }
+}
+
+impl World {
+    pub fn new(pet: Pet, seed: u64) -> Self {
+        Self {
+            pet,
+            playground: PLAYGROUND,
+            items: vec![
+                Item {
+                    kind: ItemKind::Bowl,
+                    position: (PLAYGROUND.left + 20.0, PLAYGROUND.bottom + 10.0),
+                },
+                Item {
+                    kind: ItemKind::Toy,
+                    position: (PLAYGROUND.right - 30.0, PLAYGROUND.bottom + 10.0),
+                },
+            ],
+            inventory: Inventory::default(),
+            seed,
+            rng: SimRng::seed_from_u64(seed),
+            behavior: Behavior::default(),
+            game: None,
+        }
+    }
+
+    /// What the pet is doing when left to its own devices.
+    pub fn activity(&self) -> Activity {
+        self.behavior.activity()
+    }
+
+    /// The mini-game in progress, if any. The pet's needs are paused while playing.
+    pub fn game(&self) -> Option<&Game> {
+        self.game.as_ref()
+    }
+
+    /// Advances the world by `dt`.
+    ///
+    /// While a game is running only the game moves forward. Otherwise the pet is
+    /// stepped in slices of at most [`MAX_STEP`], so `dt` can be as long as needed.
+    pub fn step(&mut self, dt: Duration) -> Vec<Event> {
+        let mut events = Vec::new();
+        if let Some(game) = &mut self.game {
+            game.update(dt, &mut self.rng);
+            if let Some(outcome) = game.outcome() {
+                let kind = game.kind();
+                self.game = None;
+                self.pet.played(outcome);
+                self.inventory.coins += shop::reward(outcome);
+                events.push(Event::GameOver { kind, outcome });
+            }
+            return events;
+        }
+        self.pass(dt, false)
+    }
+
+    /// Lets `dt` pass for the pet, in slices of at most [`MAX_STEP`]. While the
+    /// owner is `away` the pet goes by [`Pet::step_away`] instead.
+    fn pass(&mut self, dt: Duration, away: bool) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut remaining = dt;
+        while !remaining.is_zero() && !self.pet.is_dead() {
+            let slice = remaining.min(MAX_STEP);
+            remaining -= slice;
+            let changes = if away {
+                self.pet.step_away(slice, &mut self.rng)
+            } else {
+                self.pet.step(slice, &mut self.rng)
+            };
+            self.behavior.step(
+                &mut self.pet,
+                &self.items,
+                self.playground,
+                slice,
+                &mut self.rng,
+            );
+            events.extend(self.eat_dropped_food().map(Event::Ate));
+            for change in changes {
+                events.push(match change {
+                    Change::Grew {
+                        from: Stage::Egg, ..
+                    } => Event::Hatched,
+                    Change::Grew { to, .. } => Event::Grew(to),
+                    Change::Evolved(form) => Event::Evolved(form),
+                    Change::Died(cause) => {
+                        self.game = None;
+                        Event::Died(cause)
+                    }
+                    Change::FellAsleep => Event::FellAsleep,
+                    Change::WokeUp => Event::WokeUp,
+                    Change::FellIll(illness) => Event::FellIll(illness),
+                    Change::Misbehaved(misbehavior) => Event::Misbehaved(misbehavior),
+                    Change::Called(need) => Event::Called(need),
+                    Change::CallIgnored(need) => Event::CallIgnored(need),
+                    Change::MadeAMess => {
+                        // Just behind and below the pet, but still in view
+                        let (x, y) = self.pet.position;
+                        let spot = (
+                            (x - 8.0).max(self.playground.left + 3.0),
+                            (y - 8.0).max(self.playground.bottom + 3.0),
+                        );
+                        self.pet.make_mess(spot);
+                        Event::MadeAMess
+                    }
+                });
+            }
+        }
+        events
+    }
+
+    /// Feeds the pet, using up a treat from the inventory if that's what it eats.
+    pub fn feed(&mut self, food: Food) -> Result<Fed, Refusal> {
+        let product = (food == Food::Treat).then_some(Product::Treat);
+        if let Some(product) = product {
+            self.inventory.check(product)?;
+        }
+        let fed = self.pet.feed(food)?;
+        if let Some(product) = product {
+            self.inventory.take(product);
+        }
+        Ok(fed)
+    }
+
+    /// Drops `food` at `spot` for the pet to go and eat, replacing any food that
+    /// is already on the ground.
+    pub fn drop_food(&mut self, food: Food, spot: (f64, f64)) -> Result<(), Refusal> {
+        let stage = self.pet.stage();
+        if stage == Stage::Dead {
+            return Err(Refusal::Dead);
+        }
+        if !stage.can_feed() {
+            return Err(Refusal::CantEat(stage));
+        }
+        if food == Food::Treat {
+            self.inventory.check(Product::Treat)?;
+        }
+        let position = self.pet.clamped(spot.0, spot.1, self.playground);
+        self.items
+            .retain(|item| !matches!(item.kind, ItemKind::Food(_)));
+        self.items.push(Item {
+            kind: ItemKind::Food(food),
+            position,
+        });
+        self.behavior.seek(Activity::SeekFood, position);
+        Ok(())
+    }
+
+    /// Eats the food on the ground if the pet is close enough and up for it.
+    fn eat_dropped_food(&mut self) -> Option<Fed> {
+        let index = self.items.iter().position(|item| {
+            matches!(item.kind, ItemKind::Food(_))
+                && behavior::distance(item.position, self.pet.position) < REACH
+        })?;
+        let ItemKind::Food(food) = self.items[index].kind else {
+            return None;
+        };
+        // Left where it is if the pet can't eat it yet
+        let fed = self.feed(food).ok()?;
+        self.items.remove(index);
+        Some(fed)
+    }
+
+    /// Moves the item at `index` to `to`, keeping it inside the playground.
+    pub fn move_item(&mut self, index: usize, to: (f64, f64)) {
+        let position = self.pet.clamped(to.0, to.1, self.playground);
+        if let Some(item) = self.items.get_mut(index) {
+            self.behavior.retarget(item.position, position);
+            item.position = position;
+        }
+    }
+
+    /// Swaps in a playground of a different shape, moving the pet and everything
+    /// else to the same relative spots in it.
+    pub fn resize(&mut self, playground: Bounds) {
+        let from = self.playground;
+        self.playground = playground;
+        let (x, y) = from.map_to(playground, self.pet.position);
+        self.pet.position = self.pet.clamped(x, y, playground);
+        for item in &mut self.items {
+            item.position = from.map_to(playground, item.position);
+        }
+        for pile in &mut self.pet.waste {
+            *pile = from.map_to(playground, *pile);
+        }
+        self.behavior.rescale(from, playground);
+    }
+
+    /// Pets the pet. Returns whether it enjoyed it.
+    pub fn stroke(&mut self) -> Result<bool, Refusal> {
+        self.pet.stroke()
+    }
+
+    pub fn start_game(&mut self, kind: GameKind) -> Result<(), Refusal> {
+        self.pet.can_play(kind)?;
+        self.game = Some(kind.start(&mut self.rng));
+        Ok(())
+    }
+
+    /// Cures the pet with medicine from the inventory. An owner who has none and
+    /// can't afford any gets a dose for free, since a sick pet can't play games to
+    /// earn the coins.
+    pub fn give_medicine(&mut self) -> Result<Illness, Refusal> {
+        let free = self.inventory.count(Product::Medicine) == 0
+            && self.inventory.coins < Product::Medicine.price();
+        if !free {
+            self.inventory.check(Product::Medicine)?;
+        }
+        let cured = self.pet.give_medicine()?;
+        self.inventory.take(Product::Medicine);
+        Ok(cured)
+    }
+
+    pub fn buy(&mut self, product: Product) -> Result<(), Refusal> {
+        self.inventory.buy(product)
+    }
+
+    pub fn respond(&mut self, response: Response) -> Result<Reaction, Refusal> {
+        self.pet.respond(response)
+    }
+
+    pub fn clean(&mut self) -> Result<usize, Refusal> {
+        self.pet.clean()
+    }
+
+    pub fn sleep(&mut self) -> Result<(), Refusal> {
+        self.pet.sleep()
+    }
+
+    pub fn wake(&mut self) -> Result<(), Refusal> {
+        self.pet.wake()
+    }
+
+    /// Advances the world by `offline`, the time that passed while the game was
+    /// closed. A game left running is abandoned first, so all of that time goes to
+    /// the pet, which is kept from coming to harm while nobody is around to look
+    /// after it.
+    pub fn catch_up(&mut self, offline: Duration) -> Vec<Event> {
+        self.stop_game();
+        self.pass(offline, true)
+    }
+
+    /// Abandons the current game without any reward or cost.
+    pub fn stop_game(&mut self) {
+        self.game = None;
+    }
+
+    pub fn game_input(&mut self, side: Side) {
+        if let Some(game) = &mut self.game {
+            game.input(side, &mut self.rng);
+        }
+    }
+
+    /// Moves the pet by `(dx, dy)` within the playground.
+    pub fn move_pet(&mut self, dx: f64, dy: f64) {
+        self.pet.move_by(dx, dy, self.playground);
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Hatched => write!(f, "The egg hatched!"),
+            Event::Grew(stage) => write!(f, "Grew into a {}!", stage.name()),
+            Event::Evolved(form) => write!(f, "It's a {}!", form.name()),
+            Event::Died(cause) => write!(f, "{}", cause.describe()),
+            Event::FellAsleep => write!(f, "Fell asleep. Zzz..."),
+            Event::WokeUp => write!(f, "Woke up full of energy"),
+            Event::Misbehaved(Misbehavior::Fussy) => {
+                write!(f, "Your pet is being fussy")
+            }
+            Event::Misbehaved(Misbehavior::FakeCall) => {
+                write!(f, "Your pet is calling you... but seems fine")
+            }
+            Event::Called(need) => write!(f, "Your pet is calling: {}!", need.name()),
+            Event::CallIgnored(need) => write!(
+                f,
+                "Nobody came when your pet was {} (care mistake)",
+                need.name().to_lowercase()
+            ),
+            Event::MadeAMess => write!(f, "Your pet made a mess"),
+            Event::Ate(fed) => write!(f, "{fed}"),
+            Event::FellIll(illness) => {
+                write!(f, "Came down with a {}!", illness.name().to_lowercase())
+            }
+            Event::GameOver { kind, outcome } => {
+                write!(
+                    f,
+                    "{} {}: {}/{}",
+                    if outcome.won { "Won" } else { "Lost" },
+                    kind.name(),
+                    outcome.score,
+                    outcome.out_of
+                )?;
+                match shop::reward(*outcome) {
+                    0 => Ok(()),
+                    coins => write!(f, " (+{coins} coins)"),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Fed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.overfed {
+            write!(f, "Ate a {} but was already full. Ugh...", self.food.name())
+        } else {
+            write!(f, "Ate a {}. Yum!", self.food.name())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_steps_are_split() {
+        let mut world = World::default();
+        let mut events = world.step(Duration::from_secs(15 * 60));
+        events.retain(|event| matches!(event, Event::Hatched | Event::Grew(_)));
+        assert_eq!(events, [Event::Hatched, Event::Grew(Stage::Child)]);
+        assert_eq!(world.pet.tick_count, 15 * 60);
+    }
+
+    #[test]
+    fn neglected_babies_grow_into_scamps() {
+        let mut world = World::default();
+        world.step(Duration::from_secs(10 * 60));
+        assert!(world.pet.care_mistakes > 0);
+        assert_eq!(world.pet.form, Some(Form::Scamp));
+    }
+
+    #[test]
+    fn pets_live_through_a_night_away() {
+        let night = Duration::from_secs(8 * 60 * 60);
+        for minutes in [20, 5 * 60] {
+            for seed in 0..5 {
+                let mut pet = Pet::new();
+                pet.age = Duration::from_secs(minutes * 60);
+                let mut world = World::new(pet, seed);
+                let events = world.catch_up(night);
+                assert!(!world.pet.is_dead(), "{events:?}");
+                assert_eq!(world.pet.age, Duration::from_secs(minutes * 60) + night);
+                assert_eq!(world.pet.care_mistakes, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn needs_are_paused_while_playing() {
+        let mut world = World::default();
+        world.pet.age = Duration::from_secs(20 * 60);
+        world.start_game(GameKind::Guess).unwrap();
+        world.step(Duration::from_secs(60));
+        assert_eq!(world.pet.hunger, 0);
+        assert_eq!(world.pet.age, Duration::from_secs(20 * 60));
+    }
+
+    #[test]
+    fn finishing_a_game_costs_energy() {
+        let mut world = World::default();
+        world.pet.age = Duration::from_secs(20 * 60);
+        world.start_game(GameKind::Guess).unwrap();
+        for _ in 0..5 {
+            world.game_input(Side::Left);
+        }
+        let events = world.step(Duration::from_millis(16));
+        assert!(matches!(events[..], [Event::GameOver { .. }]));
+        assert!(world.game().is_none());
+        assert_eq!(world.pet.energy, 100 - crate::play::PLAY_ENERGY_COST);
+    }
+
+    #[test]
+    fn sick_pets_can_always_be_cured() {
+        for (coins, medicine) in [(0, 0), (0, 1), (14, 0), (15, 0), (100, 0), (100, 3)] {
+            let mut world = World::default();
+            world.pet.age = Duration::from_secs(20 * 60);
+            world.pet.illness = Some(Illness::Cold);
+            world.inventory = Inventory {
+                coins,
+                stock: [0, medicine],
+            };
+            if world.give_medicine() == Err(Refusal::NoneLeft(Product::Medicine)) {
+                world.buy(Product::Medicine).unwrap();
+                world.give_medicine().unwrap();
+            }
+            assert_eq!(
+                world.pet.illness, None,
+                "{coins} coins, {medicine} medicine"
+            );
+        }
+    }
+
+    #[test]
+    fn dropped_food_gets_eaten() {
+        let mut world = World::default();
+        world.pet.age = Duration::from_secs(20 * 60);
+        world.pet.hunger = 80;
+        let (x, y) = world.pet.position;
+        world.drop_food(Food::Meal, (x + 30.0, y)).unwrap();
+        assert_eq!(world.activity(), Activity::SeekFood);
+        let mut events = Vec::new();
+        for _ in 0..30 {
+            events.extend(world.step(Duration::from_secs(1)));
+        }
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::Ate(Fed {
+                food: Food::Meal,
+                ..
+            })
+        )));
+        assert!(!world
+            .items
+            .iter()
+            .any(|item| matches!(item.kind, ItemKind::Food(_))));
+        assert!(world.pet.hunger < 80);
+    }
+
+    #[test]
+    fn eggs_cant_have_food_dropped() {
+        let mut world = World::default();
+        assert_eq!(
+            world.drop_food(Food::Meal, (50.0, 50.0)),
+            Err(Refusal::CantEat(Stage::Egg))
+        );
+        assert_eq!(world.items.len(), 2);
+    }
+
+    #[test]
+    fn resizing_keeps_things_in_place() {
+        let mut world = World::default();
+        world.pet.position = (60.0, 35.0);
+        let toy = world.items[1].position;
+        let wide = Bounds::fitting(4.0);
+        world.resize(wide);
+        assert_eq!(world.playground, wide);
+        assert_eq!(world.pet.position, PLAYGROUND.map_to(wide, (60.0, 35.0)));
+        assert_eq!(world.pet.position.1, 35.0);
+        world.resize(PLAYGROUND);
+        assert!((world.pet.position.0 - 60.0).abs() < 1e-9);
+        assert!((world.items[1].position.0 - toy.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn seeds_decide_the_personality() {
+        let hatch = |seed| {
+            let mut world = World::new(Pet::new(), seed);
+            world.step(Duration::from_secs(5 * 60));
+            world.pet.traits
+        };
+        assert_eq!(hatch(7), hatch(7));
+        assert!((0..8).any(|seed| hatch(seed) != hatch(7)));
+    }
+
+    #[test]
+    fn dead_pets_stop_aging() {
+        let mut world = World::default();
+        world.pet.age = Duration::from_secs(96 * 60 * 60 - 1);
+        let events = world.step(Duration::from_secs(10));
+        assert_eq!(events, [Event::Died(DeathCause::OldAge)]);
+        assert_eq!(world.pet.age, Duration::from_secs(96 * 60 * 60));
+    }
+}
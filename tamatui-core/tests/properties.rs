@@ -0,0 +1,155 @@
+//! Property tests for the simulation rules.
+
+use std::time::Duration;
+
+use proptest::prelude::*;
+use tamatui_core::{
+    food::Food,
+    personality::Response,
+    play::{GameKind, Side},
+    Pet, World,
+};
+
+#[derive(Debug, Clone)]
+enum Action {
+    Step(Duration),
+    Feed(Food),
+    Play(GameKind),
+    Input(Side),
+    StopGame,
+    Move(f64, f64),
+    ToggleSleep,
+    Medicine,
+    Clean,
+    Respond(Response),
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (0u64..600_000).prop_map(|millis| Action::Step(Duration::from_millis(millis))),
+        prop::sample::select(Food::ALL.to_vec()).prop_map(Action::Feed),
+        prop::sample::select(GameKind::ALL.to_vec()).prop_map(Action::Play),
+        prop_oneof![Just(Side::Left), Just(Side::Right)].prop_map(Action::Input),
+        Just(Action::StopGame),
+        Just(Action::ToggleSleep),
+        Just(Action::Medicine),
+        Just(Action::Clean),
+        prop_oneof![Just(Response::Scold), Just(Response::Praise)].prop_map(Action::Respond),
+        (-300.0..300.0, -300.0..300.0).prop_map(|(dx, dy)| Action::Move(dx, dy)),
+    ]
+}
+
+fn run(seed: u64, actions: &[Action]) -> World {
+    let mut world = World::new(Pet::new(), seed);
+    for action in actions {
+        match *action {
+            Action::Step(dt) => {
+                world.step(dt);
+            }
+            Action::Feed(food) => {
+                let _ = world.feed(food);
+            }
+            Action::Play(kind) => {
+                let _ = world.start_game(kind);
+            }
+            Action::Input(side) => world.game_input(side),
+            Action::StopGame => world.stop_game(),
+            Action::Move(dx, dy) => world.move_pet(dx, dy),
+            Action::Respond(response) => {
+                let _ = world.respond(response);
+            }
+            Action::Clean => {
+                let _ = world.clean();
+            }
+            Action::Medicine => {
+                let _ = world.give_medicine();
+            }
+            Action::ToggleSleep => {
+                if world.pet.asleep {
+                    let _ = world.wake();
+                } else {
+                    let _ = world.sleep();
+                }
+            }
+        }
+    }
+    world
+}
+
+proptest! {
+    #[test]
+    fn stats_stay_in_range(seed: u64, actions in prop::collection::vec(action(), 0..64)) {
+        let world = run(seed, &actions);
+        let pet = &world.pet;
+        prop_assert!(pet.hunger <= 100);
+        prop_assert!(pet.happiness <= 100);
+        prop_assert!(pet.health <= 100);
+        prop_assert!(pet.energy <= 100);
+        prop_assert!(pet.cleanliness <= 100);
+        prop_assert!(pet.discipline <= 100);
+        prop_assert!(pet.weight >= 1);
+        let (x, y) = pet.position;
+        prop_assert!(x >= world.playground.left && x <= world.playground.right);
+        prop_assert!(y >= world.playground.bottom && y <= world.playground.top);
+        prop_assert!(pet.waste.len() <= tamatui_core::hygiene::MAX_WASTE);
+    }
+
+    #[test]
+    fn same_seed_and_inputs_give_the_same_pet(
+        seed: u64,
+        actions in prop::collection::vec(action(), 0..64),
+    ) {
+        let first = serde_json::to_string(&run(seed, &actions).pet).unwrap();
+        let second = serde_json::to_string(&run(seed, &actions).pet).unwrap();
+        prop_assert_eq!(first, second);
+    }
+
+    #[test]
+    fn one_long_step_matches_many_short_ones(secs in 0u64..3600) {
+        let mut long = World::default();
+        long.step(Duration::from_secs(secs));
+        let mut short = World::default();
+        for _ in 0..secs {
+            short.step(Duration::from_secs(1));
+        }
+        prop_assert_eq!(
+            serde_json::to_string(&long.pet).unwrap(),
+            serde_json::to_string(&short.pet).unwrap()
+        );
+    }
+
+    #[test]
+    fn reloading_carries_on_exactly(seed: u64, before in 0u64..1800, after in 0u64..1800) {
+        let mut whole = World::new(Pet::new(), seed);
+        whole.step(Duration::from_secs(before + after));
+        let mut saved = World::new(Pet::new(), seed);
+        saved.step(Duration::from_secs(before));
+        let mut reloaded = reload(&saved);
+        reloaded.step(Duration::from_secs(after));
+        prop_assert_eq!(
+            serde_json::to_value(&whole).unwrap(),
+            serde_json::to_value(&reloaded).unwrap()
+        );
+    }
+
+    #[test]
+    fn reloading_keeps_games_and_items(
+        seed: u64,
+        actions in prop::collection::vec(action(), 0..32),
+        after in 0u64..600,
+    ) {
+        let mut kept = run(seed, &actions);
+        let mut reloaded = reload(&kept);
+        kept.step(Duration::from_secs(after));
+        reloaded.step(Duration::from_secs(after));
+        prop_assert_eq!(
+            serde_json::to_value(&kept).unwrap(),
+            serde_json::to_value(&reloaded).unwrap()
+        );
+    }
+}
+
+/// The world as it comes back from being saved.
+fn reload(world: &World) -> World {
+    serde_json::from_str(&serde_json::to_string(world).unwrap()).unwrap()
+}